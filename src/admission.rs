@@ -0,0 +1,67 @@
+//! Implementations of cache admission policies.
+
+use std::collections::HashSet;
+
+use crate::item::Item;
+
+/// An abstracted cache admission policy, which decides whether a missed item is inserted into the
+/// cache at all.
+///
+/// This is only consulted on misses. An item which isn't admitted is still counted as a miss by
+/// the cache's statistic, but it isn't inserted, so nothing is evicted and the replacement policy
+/// only sees it through [`ReplacementPolicy::bypass`](crate::ReplacementPolicy::bypass).
+pub trait AdmissionPolicy<I: Item>: Default {
+    /// Decide whether to insert `item`, which is not in the cache.
+    fn admit(&mut self, item: I) -> bool;
+
+    /// Forget everything the policy has seen, as when the cache is cleared, but keep its
+    /// configuration.
+    ///
+    /// By default this resets the policy to its default.
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// The admission policy which admits every item, which is demand caching.
+#[derive(Default, Debug)]
+pub struct AdmitAll;
+
+impl<I: Item> AdmissionPolicy<I> for AdmitAll {
+    fn admit(&mut self, _: I) -> bool {
+        true
+    }
+}
+
+/// An admission policy which only admits items the second time they miss, so that items which
+/// are only accessed once never enter the cache.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, Lru};
+/// use cache_sim::admission::AdmitRepeated;
+///
+/// let mut c = Cache::<Lru, (), u32, AdmitRepeated>::new(2);
+///
+/// c.access(0);
+/// c.access(0); // 0 is admitted on its second access
+/// c.access(1);
+/// c.access(1); // and so is 1
+///
+/// // a scan of items which are only accessed once doesn't disturb the cache
+/// for i in 2..10 {
+///     c.access(i);
+/// }
+///
+/// assert_eq!(c.set(), &HashSet::from([0, 1]));
+/// ```
+#[derive(Default, Debug)]
+pub struct AdmitRepeated<I: Item = u32> {
+    seen: HashSet<I>,
+}
+
+impl<I: Item> AdmissionPolicy<I> for AdmitRepeated<I> {
+    fn admit(&mut self, item: I) -> bool {
+        !self.seen.insert(item)
+    }
+}
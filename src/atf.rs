@@ -15,6 +15,9 @@
 
 use serde::Deserialize;
 
+use crate::item::{Item, MultiCostItem};
+use crate::{GeneralModelItem, Trace};
+
 #[derive(Debug, Deserialize, PartialEq)]
 enum Operation {
     #[serde(alias = "R")]
@@ -35,13 +38,70 @@ pub struct OpRecord {
     cost: Vec<f64>,
 }
 
-impl From<OpRecord> for crate::GeneralModelItem {
+impl OpRecord {
+    /// The time of the operation, in nanoseconds since the trace's zero.
+    #[must_use]
+    pub fn timestamp(&self) -> u64 {
+        u64::from(self.nanos_since_zero)
+    }
+
+    /// Convert the record into an item, using the cost column with the given index. The first
+    /// cost column has index zero.
+    ///
+    /// Returns `None` if the record doesn't have that many cost columns.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), csv::Error> {
+    /// use cache_sim::atf::parse;
+    /// use cache_sim::item::Item;
+    ///
+    /// let records = parse(b"0,1,R,1,7,2.5".as_slice())?;
+    /// assert_eq!(records[0].to_item(1).map(|i| i.cost()), Some(2.5));
+    /// assert_eq!(records[0].to_item(2), None);
+    /// # Ok(())}
+    /// ```
+    #[must_use]
+    pub fn to_item(&self, cost_column: usize) -> Option<GeneralModelItem> {
+        let item = GeneralModelItem::new(
+            self.accessed_item_id,
+            *self.cost.get(cost_column)?,
+            self.size,
+        );
+
+        Some(match self.optype {
+            Operation::Read => item,
+            Operation::Write => item.as_write(),
+        })
+    }
+
+    /// Convert the record into an item which keeps its first `N` cost columns, with the first as
+    /// the primary cost.
+    ///
+    /// Returns `None` if the record has fewer than `N` cost columns.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), csv::Error> {
+    /// use cache_sim::atf::parse;
+    ///
+    /// let records = parse(b"0,1,R,1,7,2.5".as_slice())?;
+    /// assert_eq!(records[0].to_multi_cost_item::<2>().map(|i| *i.costs()), Some([7.0, 2.5]));
+    /// assert_eq!(records[0].to_multi_cost_item::<3>(), None);
+    /// # Ok(())}
+    /// ```
+    #[must_use]
+    pub fn to_multi_cost_item<const N: usize>(&self) -> Option<MultiCostItem<N>> {
+        let costs = self.cost.get(..N)?.try_into().ok()?;
+        Some(MultiCostItem::new(self.accessed_item_id, costs, self.size))
+    }
+}
+
+/// Convert a record into an item using its first cost column, which [`parse`] makes sure that
+/// every record has. To use a different column, see [`OpRecord::to_item`].
+impl From<OpRecord> for GeneralModelItem {
     fn from(record: OpRecord) -> Self {
-        Self::new(
-            record.accessed_item_id,
-            record.cost[0], // TODO: something better
-            record.size,
-        )
+        record
+            .to_item(0)
+            .expect("The record has at least one cost column.")
     }
 }
 
@@ -66,15 +126,204 @@ impl From<OpRecord> for crate::GeneralModelItem {
 /// # Ok(())}
 /// ````
 pub fn parse<R: std::io::Read>(input: R) -> Result<Vec<OpRecord>, csv::Error> {
+    parse_iter(input)
+        // `Result` implements fromiterator, so when we collect this it will give us the first
+        // error if there are any errors, or else will give us the vector of [`OpRecord`]s.
+        .collect()
+}
+
+/// Lazily parse a file-like object into oprecords, one row at a time.
+///
+/// Unlike [`parse`], this never holds the whole file in memory, so it's suitable for simulating
+/// huge traces by feeding each item straight to a cache. Building a [`Trace`] still needs every
+/// item, though.
+///
+/// Each item is `Err` if that row does not conform to the `atf` standard.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), csv::Error> {
+/// use cache_sim::{atf::parse_iter, Cache, GeneralModelItem, Lru};
+/// use cache_sim::stats::HitCount;
+///
+/// let mut c = Cache::<Lru<_>, HitCount, _>::new(100);
+/// for record in parse_iter(std::fs::File::open("huge.atf")?) {
+///     c.access(GeneralModelItem::from(record?));
+/// }
+/// # Ok(())}
+/// ```
+pub fn parse_iter<R: std::io::Read>(
+    input: R,
+) -> impl Iterator<Item = Result<OpRecord, csv::Error>> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .comment(Some(b'#'))
+        .from_reader(input)
+        .into_deserialize()
+}
+
+/// An error from [`parse_checked`].
+#[derive(Debug)]
+pub enum AtfError {
+    /// The csv couldn't be read, or a row doesn't conform to the `atf` standard.
+    Csv(csv::Error),
+    /// A row has an earlier timestamp than the row before it.
+    OutOfOrder {
+        /// The line of the row in the file, starting from one.
+        line: u64,
+        /// The row's timestamp.
+        timestamp: u64,
+        /// The timestamp of the row before it.
+        previous: u64,
+    },
+    /// A row doesn't have any cost columns.
+    MissingCost {
+        /// The line of the row in the file, starting from one.
+        line: u64,
+    },
+}
+
+impl std::fmt::Display for AtfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv(e) => write!(f, "{e}"),
+            Self::OutOfOrder {
+                line,
+                timestamp,
+                previous,
+            } => write!(
+                f,
+                "line {line}: timestamp {timestamp} is before the previous timestamp {previous}"
+            ),
+            Self::MissingCost { line } => write!(f, "line {line}: there are no cost columns"),
+        }
+    }
+}
+
+impl std::error::Error for AtfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Csv(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<csv::Error> for AtfError {
+    fn from(e: csv::Error) -> Self {
+        Self::Csv(e)
+    }
+}
+
+/// Parse a file-like object into a vector of oprecords, checking that the trace makes sense.
+///
+/// Unlike [`parse`], this requires that the timestamps never go backwards, which usually means
+/// that the trace is corrupt or was concatenated in the wrong order, and it reports rows without
+/// any cost columns with their line number.
+///
+/// # Errors
+/// If the csv does not conform to the `atf` standard, if a timestamp is earlier than the one
+/// before it, or if a row has no cost columns.
+///
+/// # Example
+///
+/// ```
+/// use cache_sim::atf::{parse_checked, AtfError};
+///
+/// let records = parse_checked(b"0,1,R,1,1\n1,1,R,1,1\n0,2,R,1,1".as_slice());
+/// assert_eq!(records.map(|r| r.len()).ok(), Some(3));
+///
+/// let records = parse_checked(b"0,2,R,1,1\n1,1,R,1,1".as_slice());
+/// assert!(matches!(records, Err(AtfError::OutOfOrder { line: 2, .. })));
+/// ```
+pub fn parse_checked<R: std::io::Read>(input: R) -> Result<Vec<OpRecord>, AtfError> {
     let mut rdr = csv::ReaderBuilder::new()
         .has_headers(false)
         .comment(Some(b'#'))
+        // so that a missing cost column is our error rather than a length mismatch
+        .flexible(true)
         .from_reader(input);
 
-    rdr.deserialize()
-        // `Result` implements fromiterator, so when we collect this it will give us the first
-        // error if there are any errors, or else will give us the vector of [`OpRecord`]s.
-        .collect()
+    let mut records: Vec<OpRecord> = Vec::new();
+
+    for row in rdr.records() {
+        let row = row?;
+        let line = row.position().map_or(0, csv::Position::line);
+
+        // the four fixed columns, and then the costs
+        if row.len() == 4 {
+            return Err(AtfError::MissingCost { line });
+        }
+
+        let record: OpRecord = row.deserialize(None)?;
+        if let Some(previous) = records.last() {
+            if record.timestamp() < previous.timestamp() {
+                return Err(AtfError::OutOfOrder {
+                    line,
+                    timestamp: record.timestamp(),
+                    previous: previous.timestamp(),
+                });
+            }
+        }
+
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Write a trace to a file-like object in the `atf` format.
+///
+/// The trace's timestamps are written if it has them, like a trace read with
+/// [`Trace::from_records`]. Otherwise, the timestamp of each access is its position in the trace,
+/// since items don't remember when they were accessed. Each item's cost is written as the only
+/// cost column, and writes are written as `W`.
+///
+/// # Errors
+/// If writing to the output fails.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), csv::Error> {
+/// use cache_sim::{atf, GeneralModelItem, Trace};
+///
+/// let trace = Trace::from(vec![
+///     GeneralModelItem::new(0, 1.5, 2),
+///     GeneralModelItem::new(1, 1.0, 1).as_write(),
+/// ]);
+///
+/// let mut out = Vec::new();
+/// atf::write(&trace, &mut out)?;
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "# item id, timestamp, operation, size, cost\n0,0,R,2,1.5\n1,1,W,1,1\n"
+/// );
+/// # Ok(())}
+/// ```
+pub fn write<W: std::io::Write>(
+    trace: &Trace<GeneralModelItem>,
+    mut writer: W,
+) -> Result<(), csv::Error> {
+    writeln!(writer, "# item id, timestamp, operation, size, cost")?;
+
+    let mut wtr = csv::Writer::from_writer(writer);
+    for (position, item) in trace.iter().enumerate() {
+        let timestamp = trace
+            .timestamps()
+            .map_or_else(|| position.to_string(), |t| t[position].to_string());
+        wtr.write_record(&[
+            item.id().to_string(),
+            timestamp,
+            String::from(if item.is_write() { "W" } else { "R" }),
+            item.size().to_string(),
+            item.cost().to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -138,4 +387,216 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn optype_is_kept() -> Result<(), csv::Error> {
+        const DATA: &[u8] = b"# item id, timestamp, operation, bytes, latency (ns)
+0,1,R,1,1
+0,2,W,1,1";
+
+        let items = parse(DATA)?
+            .into_iter()
+            .map(GeneralModelItem::from)
+            .collect::<Vec<_>>();
+        assert!(!items[0].is_write());
+        assert!(items[1].is_write());
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), csv::Error> {
+        const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7,6
+0,16,W,3,4,2.5
+1,4,R,3,2,1.2";
+
+        let to_items = |records: Vec<OpRecord>| {
+            records
+                .into_iter()
+                .map(GeneralModelItem::from)
+                .collect::<Trace<_>>()
+        };
+
+        let trace = to_items(parse(DATA)?);
+        let mut out = Vec::new();
+        write(&trace, &mut out)?;
+        let round_tripped = to_items(parse(out.as_slice())?);
+
+        // items are equal if their ids are, so check everything else too
+        let fields = |trace: &Trace<GeneralModelItem>| {
+            trace
+                .iter()
+                .map(|i| (i.id(), i.cost(), i.size(), i.is_write()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(fields(&round_tripped), fields(&trace));
+        assert_eq!(round_tripped, trace);
+
+        Ok(())
+    }
+
+    #[test]
+    fn timestamps_round_trip() -> Result<(), csv::Error> {
+        const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7,6
+0,16,W,3,4,2.5
+1,40,R,3,2,1.2";
+
+        let trace = Trace::from_records(parse(DATA)?);
+        let mut out = Vec::new();
+        write(&trace, &mut out)?;
+        let round_tripped = Trace::from_records(parse(out.as_slice())?);
+
+        assert_eq!(round_tripped.timestamps(), Some([2, 16, 40].as_slice()));
+        assert_eq!(round_tripped, trace);
+
+        Ok(())
+    }
+
+    #[test]
+    fn select_cost_column() -> Result<(), csv::Error> {
+        const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7,6
+0,16,W,3,4,2.5
+1,4,R,3,2,1.2";
+
+        let costs = parse(DATA)?
+            .iter()
+            .map(|r| r.to_item(1).map(|i| i.cost()))
+            .collect::<Vec<_>>();
+        assert_eq!(costs, vec![Some(6.0), Some(2.5), Some(1.2)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty_cost() {
+        // there's no cost column, so there's nothing to convert the record with
+        const DATA: &[u8] = b"0,1,R,1";
+
+        assert!(parse(DATA).is_err());
+    }
+
+    #[test]
+    fn multi_cost_items() -> Result<(), csv::Error> {
+        const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7,6
+0,16,W,3,4,2.5
+1,4,R,3,2,1.2";
+
+        let items = parse(DATA)?
+            .iter()
+            .map(|r| r.to_multi_cost_item::<2>().expect("there are two costs"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(items[1].cost(), 4.0);
+        assert_eq!(items[1].with_primary(1).cost(), 2.5);
+        assert_eq!(items[2].costs(), &[2.0, 1.2]);
+        assert_eq!(items[2].size(), 3);
+        // the items are still only identified by their ids
+        assert_eq!(items[0], items[2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_parser() -> Result<(), csv::Error> {
+        const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7,6
+0,16,W,3,4,2.5
+not,a,valid,row";
+
+        let mut records = parse_iter(DATA);
+
+        assert_eq!(
+            records.next().transpose()?.map(|r| r.accessed_item_id),
+            Some(1)
+        );
+        assert_eq!(
+            records.next().transpose()?.map(|r| r.optype),
+            Some(Operation::Write)
+        );
+        // the bad row only errors once we get to it
+        assert!(records.next().expect("there's a third row").is_err());
+        assert!(records.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn optype_reaches_the_cache() -> Result<(), csv::Error> {
+        use crate::stats::ReadWriteHits;
+        use crate::{Cache, Lru};
+
+        const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7,6
+0,16,W,3,4,2.5
+1,4,R,3,2,1.2
+0,5,W,3,4,2.5";
+
+        let mut c = Cache::<Lru<_>, ReadWriteHits, _>::new(10);
+        c.run_trace(
+            &parse(DATA)?
+                .into_iter()
+                .map(GeneralModelItem::from)
+                .collect(),
+        );
+
+        assert_eq!((c.stat().reads.0, c.stat().reads.1), (1, 2));
+        assert_eq!((c.stat().writes.0, c.stat().writes.1), (1, 2));
+
+        Ok(())
+    }
+    mod checked {
+        use super::*;
+
+        #[test]
+        fn matches_parse() -> Result<(), AtfError> {
+            const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7,6
+0,16,W,3,4,2.5
+1,16,R,3,2,1.2";
+
+            assert_eq!(parse_checked(DATA)?, parse(DATA)?);
+
+            Ok(())
+        }
+
+        #[test]
+        fn out_of_order() {
+            const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7,6
+0,16,W,3,4,2.5
+1,4,R,3,2,1.2";
+
+            assert!(parse(DATA).is_ok());
+            match parse_checked(DATA) {
+                Err(e @ AtfError::OutOfOrder { .. }) => assert_eq!(
+                    e.to_string(),
+                    "line 4: timestamp 4 is before the previous timestamp 16"
+                ),
+                other => panic!("expected an out of order error, got {other:?}"),
+            }
+        }
+
+        #[test]
+        fn empty_cost() {
+            const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7
+0,16,W,3";
+
+            assert!(matches!(
+                parse_checked(DATA),
+                Err(AtfError::MissingCost { line: 3 })
+            ));
+        }
+
+        #[test]
+        fn malformed() {
+            const DATA: &[u8] = b"1,2,X,4,7";
+
+            assert!(matches!(parse_checked(DATA), Err(AtfError::Csv(_))));
+        }
+    }
 }
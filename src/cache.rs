@@ -2,16 +2,39 @@
 
 use std::collections::HashSet;
 use std::fmt::Display;
+use std::marker::PhantomData;
 
 use itertools::Itertools;
 
+use crate::admission::{AdmissionPolicy, AdmitAll};
 use crate::item::Item;
 use crate::replacement_policy::ReplacementPolicy;
-use crate::stats::Stat;
+use crate::stats::{HitCount, Stat};
 use crate::trace::Trace;
 
+/// The result of a single access to a cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessOutcome<I: Item> {
+    /// The item was already in the cache.
+    Hit,
+    /// The item wasn't in the cache. If it was admitted, `evicted` holds the items that were
+    /// evicted to make room for it.
+    Miss { evicted: HashSet<I> },
+}
+
+impl<I: Item> AccessOutcome<I> {
+    /// Check whether the access was a hit.
+    #[must_use]
+    pub const fn is_hit(&self) -> bool {
+        matches!(self, Self::Hit)
+    }
+}
+
 /// A cache, generic over a replacement policy and set of statistics.
 ///
+/// By default this is a demand cache, so every accessed item is inserted; a different
+/// [`AdmissionPolicy`] can decide whether missed items are inserted at all.
+///
 /// ```
 /// # use std::collections::HashSet;
 /// use cache_sim::{Cache, Lru};
@@ -27,14 +50,26 @@ use crate::trace::Trace;
 /// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
 /// ```
 ///
-pub struct Cache<R: ReplacementPolicy<I>, S: Stat<I> = (), I: Item = u32> {
+pub struct Cache<
+    R: ReplacementPolicy<I>,
+    S: Stat<I> = (),
+    I: Item = u32,
+    A: AdmissionPolicy<I> = AdmitAll,
+> {
     set: HashSet<I>,
     replacement_policy: R,
     capacity: u32,
     stat: S,
+    admission_policy: A,
+    observer: Option<Observer<I>>,
 }
 
-impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item> Cache<R, S, I> {
+/// A callback which is told about every access to a cache.
+///
+/// This has to be `Send`, so that the cache still is.
+type Observer<I> = Box<dyn FnMut(I, &AccessOutcome<I>) + Send>;
+
+impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item, A: AdmissionPolicy<I>> Cache<R, S, I, A> {
     /// Create an empty cache using an explicitly configured replacement policy.
     pub fn with_replacement_policy(policy: R, capacity: impl Into<u32>) -> Self {
         Self {
@@ -42,11 +77,161 @@ impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item> Cache<R, S, I> {
             replacement_policy: policy,
             capacity: capacity.into(),
             stat: S::default(),
+            admission_policy: A::default(),
+            observer: None,
+        }
+    }
+
+    /// Replace the cache's statistic with an explicitly configured one.
+    ///
+    /// ```
+    /// use cache_sim::{Cache, Lru};
+    /// use cache_sim::stats::{HitCount, Warmup};
+    ///
+    /// let c = Cache::<Lru, Warmup<HitCount>>::new(3).with_stat(Warmup::new(10));
+    /// ```
+    #[must_use]
+    pub fn with_stat(mut self, stat: S) -> Self {
+        self.stat = stat;
+        self
+    }
+
+    /// Replace the cache's admission policy with an explicitly configured one.
+    #[must_use]
+    pub fn with_admission_policy(mut self, admission_policy: A) -> Self {
+        self.admission_policy = admission_policy;
+        self
+    }
+
+    /// Call `callback` after every access, with the accessed item and the outcome of the access.
+    ///
+    /// This is lighter than writing a [`Stat`] for ad-hoc instrumentation, like logging or
+    /// progress reporting. Installing a callback replaces any previous one; without one, accesses
+    /// don't do any extra work.
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use cache_sim::{Cache, Lru, Trace};
+    ///
+    /// let t = Trace::from(vec![0, 1, 2, 0, 3, 0]);
+    ///
+    /// let accesses = Arc::new(AtomicUsize::new(0));
+    /// let hits = Arc::new(AtomicUsize::new(0));
+    ///
+    /// let mut c = Cache::<Lru>::new(3);
+    /// c.on_access({
+    ///     let (accesses, hits) = (Arc::clone(&accesses), Arc::clone(&hits));
+    ///     move |_, outcome| {
+    ///         accesses.fetch_add(1, Ordering::Relaxed);
+    ///         if outcome.is_hit() {
+    ///             hits.fetch_add(1, Ordering::Relaxed);
+    ///         }
+    ///     }
+    /// });
+    /// c.run_trace(&t);
+    ///
+    /// assert_eq!(accesses.load(Ordering::Relaxed), t.len());
+    /// assert_eq!(hits.load(Ordering::Relaxed), 2);
+    /// ```
+    pub fn on_access(&mut self, callback: impl FnMut(I, &AccessOutcome<I>) + Send + 'static) {
+        self.observer = Some(Box::new(callback));
+    }
+
+    /// Tell the observer, if there is one, about an access.
+    fn observe(&mut self, item: I, outcome: &AccessOutcome<I>) {
+        if let Some(observer) = &mut self.observer {
+            observer(item, outcome);
+        }
+    }
+
+    /// Empty the cache and reset its statistic and admission policy, replacing the replacement
+    /// policy with an explicitly configured one. The capacity is unchanged, and so is the
+    /// configuration of the statistic and admission policy; see [`Stat::reset`].
+    ///
+    /// ```
+    /// use cache_sim::{Cache, LruK, Trace};
+    /// use cache_sim::stats::HitCount;
+    ///
+    /// let mut c = Cache::<LruK, HitCount>::with_replacement_policy(LruK::new(3), 2_u32);
+    /// c.run_trace(&Trace::from(vec![0, 0, 1]));
+    ///
+    /// c.clear_with_replacement_policy(LruK::new(3));
+    /// assert!(c.set().is_empty());
+    /// assert_eq!(c.stat().0, 0);
+    /// ```
+    pub fn clear_with_replacement_policy(&mut self, policy: R) {
+        self.set.clear();
+        self.replacement_policy = policy;
+        self.stat.reset();
+        self.admission_policy.reset();
+    }
+
+    /// Change the capacity of the cache. If the items in the cache no longer fit, the replacement
+    /// policy evicts items until they do.
+    ///
+    /// Nothing is being accessed, so the statistic is only told about the evictions, through
+    /// [`Stat::shrink`].
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// use cache_sim::{Cache, Lru, Trace};
+    ///
+    /// let mut c = Cache::<Lru>::new(4);
+    /// c.run_trace(&Trace::from(vec![0, 1, 2, 3, 0]));
+    ///
+    /// // shrinking evicts the least recently used items
+    /// c.resize(2);
+    /// assert_eq!(c.set(), &HashSet::from([0, 3]));
+    ///
+    /// // growing doesn't evict anything
+    /// c.resize(3);
+    /// c.access(4);
+    /// assert_eq!(c.set(), &HashSet::from([0, 3, 4]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the replacement policy errors, and so we end up over capacity.
+    pub fn resize(&mut self, capacity: u32) {
+        self.capacity = capacity;
+
+        if self.used_capacity() > self.capacity {
+            let to_evict = self.replacement_policy.shrink(&self.set, self.capacity);
+            self.stat.shrink(&self.set, &to_evict);
+            for item in to_evict {
+                self.set.remove(&item);
+            }
         }
+
+        assert!(self.capacity >= self.used_capacity());
     }
 
-    /// Get the currently used capacity of the set of items.
-    fn used_capacity(&self) -> u32 {
+    /// Get the capacity of the cache.
+    ///
+    /// This is a `u32` rather than a float, like the rest of the cache's sizes: item sizes are
+    /// integral, so the capacity and how much of it is used are always whole numbers.
+    #[must_use]
+    pub const fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Get the currently used capacity of the set of items, which is the total of their sizes.
+    ///
+    /// ```
+    /// use cache_sim::{Cache, GeneralModelGenerator, Landlord};
+    ///
+    /// let mut c = Cache::<Landlord, (), _>::new(10);
+    /// let mut g = GeneralModelGenerator::new();
+    ///
+    /// c.access(g.item(1.0, 3));
+    /// c.access(g.item(1.0, 4));
+    ///
+    /// assert_eq!(c.used_capacity(), 7);
+    /// assert_eq!(c.capacity(), 10);
+    /// ```
+    #[must_use]
+    pub fn used_capacity(&self) -> u32 {
         self.set.iter().map(Item::size).sum()
     }
 
@@ -57,16 +242,57 @@ impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item> Cache<R, S, I> {
 
     /// Update the cache after an access to item.
     ///
+    /// If the item misses and the admission policy rejects it, the miss is recorded but the item
+    /// isn't inserted. Use [`Cache::access_outcome`] to find out what happened.
+    ///
     /// # Panics
     ///
     /// If the replacement policy errors, and so we end up over capacity.
     pub fn access(&mut self, item: I) {
-        if self.set.contains(&item) || self.has_capacity_for(item) {
-            // we're assuming demand caching for now, so here we don't need to change anything in
-            // the cache, and we just update the state of the replacement policy and the statistics
+        self.access_outcome(item);
+    }
+
+    /// Update the cache after an access to item, returning whether it hit and what was evicted.
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// use cache_sim::{AccessOutcome, Cache, Lru};
+    ///
+    /// let mut c = Cache::<Lru>::new(2);
+    ///
+    /// assert_eq!(c.access_outcome(0), AccessOutcome::Miss { evicted: HashSet::new() });
+    /// assert_eq!(c.access_outcome(1), AccessOutcome::Miss { evicted: HashSet::new() });
+    /// assert_eq!(c.access_outcome(0), AccessOutcome::Hit);
+    /// assert_eq!(c.access_outcome(2), AccessOutcome::Miss { evicted: HashSet::from([1]) });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the replacement policy errors, and so we end up over capacity.
+    pub fn access_outcome(&mut self, item: I) -> AccessOutcome<I> {
+        let outcome = self.access_unobserved(item);
+        self.observe(item, &outcome);
+        outcome
+    }
+
+    /// Update the cache after an access to item, without telling the observer.
+    fn access_unobserved(&mut self, item: I) -> AccessOutcome<I> {
+        let hit = self.set.contains(&item);
+
+        if !hit && !self.admission_policy.admit(item) {
+            self.bypass(item);
+            return AccessOutcome::Miss {
+                evicted: HashSet::new(),
+            };
+        }
+
+        let evicted = if hit || self.has_capacity_for(item) {
+            // here we don't need to evict anything, so we just update the state of the replacement
+            // policy and the statistics
             self.replacement_policy
                 .update_state(&self.set, self.capacity, item);
             self.stat.update(&self.set, item, &HashSet::new());
+            HashSet::new()
         } else {
             // here we actually need to evict something
             let to_evict = self
@@ -78,16 +304,131 @@ impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item> Cache<R, S, I> {
             // TODO: is there an easy restructuring of this that prevents us from evicting and then
             // reinserting `item`, thus ending with an over capacity cache? This can happen now if
             // the replacement policy is implemented incorrectly.
-            for item in to_evict {
-                self.set.remove(&item);
+            for item in &to_evict {
+                self.set.remove(item);
             }
-        }
 
-        // finally, again because we assume demand paging, we always have to put the last access
-        // into the cache
-        self.set.insert(item);
+            to_evict
+        };
+
+        // finally, the item was admitted, so we have to put the last access into the cache; on a
+        // hit this replaces the stored access, whose cost or size may be stale
+        self.set.replace(item);
 
         assert!(self.capacity >= self.used_capacity());
+
+        if hit {
+            AccessOutcome::Hit
+        } else {
+            AccessOutcome::Miss { evicted }
+        }
+    }
+
+    /// Update the cache after an access to item, without inserting it if it misses.
+    ///
+    /// This simulates an access which bypasses the cache, regardless of the admission policy. A
+    /// hit is handled just like [`Cache::access`]; a miss is recorded by the statistic, but
+    /// nothing is inserted or evicted, and the replacement policy only sees it through
+    /// [`ReplacementPolicy::bypass`].
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// use cache_sim::{Cache, Lru};
+    /// use cache_sim::stats::MissCount;
+    ///
+    /// let mut c = Cache::<Lru, MissCount>::new(2);
+    /// c.access(0);
+    /// c.access(1);
+    ///
+    /// c.access_no_insert(2);
+    /// assert_eq!(c.set(), &HashSet::from([0, 1]));
+    /// assert_eq!(c.stat().0, 3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the replacement policy errors, and so we end up over capacity.
+    pub fn access_no_insert(&mut self, item: I) {
+        if self.set.contains(&item) {
+            self.access(item);
+        } else {
+            self.bypass(item);
+            self.observe(
+                item,
+                &AccessOutcome::Miss {
+                    evicted: HashSet::new(),
+                },
+            );
+        }
+    }
+
+    /// Get the items which would be evicted if `incoming` were accessed now, without changing the
+    /// cache.
+    ///
+    /// This assumes that `incoming` would be admitted. For policies which draw from a shared RNG,
+    /// like [`Rand`](crate::Rand) by default, this is only a guess.
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// use cache_sim::{AccessOutcome, Cache, Lru};
+    ///
+    /// let mut c = Cache::<Lru>::new(2);
+    /// c.access(0);
+    /// c.access(1);
+    /// c.access(0);
+    ///
+    /// assert!(c.eviction_candidate(0).is_empty());
+    /// assert_eq!(c.eviction_candidate(2), HashSet::from([1]));
+    /// assert_eq!(c.access_outcome(2), AccessOutcome::Miss { evicted: HashSet::from([1]) });
+    /// ```
+    pub fn eviction_candidate(&self, incoming: I) -> HashSet<I>
+    where
+        R: Clone,
+    {
+        if self.set.contains(&incoming) || self.has_capacity_for(incoming) {
+            HashSet::new()
+        } else {
+            self.replacement_policy
+                .would_replace(&self.set, self.capacity, incoming)
+        }
+    }
+
+    /// Remove `item` from the cache, returning whether it was there.
+    ///
+    /// This simulates the item being invalidated from outside the cache, say by a write from
+    /// another client or an expiry. It isn't an access, so the statistic is only told about it
+    /// through [`Stat::invalidate`].
+    ///
+    /// ```
+    /// use cache_sim::{Cache, Lru};
+    /// use cache_sim::stats::MissCount;
+    ///
+    /// let mut c = Cache::<Lru, MissCount>::new(3);
+    /// c.access(0);
+    /// c.access(1);
+    ///
+    /// assert!(c.invalidate(0));
+    /// assert!(!c.invalidate(2));
+    ///
+    /// // re-accessing the item is a miss
+    /// c.access(0);
+    /// assert_eq!(c.stat().0, 3);
+    /// ```
+    pub fn invalidate(&mut self, item: I) -> bool {
+        // the stored access is the one which was taking up space
+        if let Some(stored) = self.set.take(&item) {
+            self.replacement_policy.remove(stored);
+            self.stat.invalidate(stored);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record a miss on item, without inserting it.
+    fn bypass(&mut self, item: I) {
+        self.replacement_policy.bypass(item);
+        self.stat.bypass(&self.set, item);
     }
 
     /// Update the cache after accessing all items in the trace.
@@ -109,18 +450,181 @@ impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item> Cache<R, S, I> {
         }
     }
 
+    /// Update the cache after accessing each item as it's produced, without collecting them into
+    /// a [`Trace`] first, e.g. for traces read with [`atf::parse_iter`](crate::atf::parse_iter)
+    /// which are too big to keep in memory.
+    ///
+    /// This works with any statistic which is computed as the cache runs, like hit and miss
+    /// counts, but there's no trace for whole-trace analyses like stack distances.
+    ///
+    /// ```
+    /// use cache_sim::{Cache, Lru, Trace};
+    /// use cache_sim::stats::HitCount;
+    ///
+    /// let items = || (0..20).map(|i| i * 7 % 5);
+    ///
+    /// let mut streamed = Cache::<Lru, HitCount>::new(3);
+    /// streamed.run_stream(items());
+    ///
+    /// let mut materialized = Cache::<Lru, HitCount>::new(3);
+    /// materialized.run_trace(&items().collect());
+    ///
+    /// assert_eq!(streamed.stat().0, materialized.stat().0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the replacement policy errors, and so we end up over capacity.
+    pub fn run_stream(&mut self, items: impl IntoIterator<Item = I>) {
+        for item in items {
+            self.access(item);
+        }
+    }
+
+    /// Update the cache after accessing all items in the trace, returning the outcome of each
+    /// access.
+    ///
+    /// ```
+    /// use cache_sim::{Cache, Lru, Trace};
+    /// use cache_sim::stats::HitCount;
+    ///
+    /// let mut c = Cache::<Lru, HitCount>::new(3);
+    /// let t = Trace::from(vec![0, 1, 2, 0, 3, 0, 1, 4, 0]);
+    ///
+    /// let outcomes = c.run_trace_outcomes(&t);
+    ///
+    /// assert_eq!(outcomes.len(), t.len());
+    /// let hits = outcomes.iter().filter(|o| o.is_hit()).count();
+    /// assert_eq!(hits, c.stat().0 as usize);
+    /// ```
+    pub fn run_trace_outcomes(&mut self, trace: &Trace<I>) -> Vec<AccessOutcome<I>> {
+        let mut outcomes = Vec::with_capacity(trace.len());
+
+        for item in trace {
+            outcomes.push(self.access_outcome(*item));
+        }
+
+        outcomes
+    }
+
+    /// Iterate over the contents of the cache after each access in the trace, running the trace
+    /// as the iterator is advanced.
+    ///
+    /// Each state is a copy of the cache's set, so this takes time and memory proportional to the
+    /// size of the cache for every access; it's meant for looking at small examples step by step.
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// use cache_sim::{Cache, Lru, Trace};
+    ///
+    /// let mut c = Cache::<Lru>::new(3);
+    /// let t = Trace::from(vec![0, 1, 2, 0, 3]);
+    ///
+    /// let states = c.states_over(&t).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(states.len(), t.len());
+    /// assert_eq!(states[1], HashSet::from([0, 1]));
+    /// assert_eq!(states.last(), Some(c.set()));
+    /// ```
+    pub fn states_over<'a>(
+        &'a mut self,
+        trace: &'a Trace<I>,
+    ) -> impl Iterator<Item = HashSet<I>> + 'a {
+        trace.iter().map(|item| {
+            self.access(*item);
+            self.set.clone()
+        })
+    }
+
     /// Get a reference to cache's statistic.
     pub const fn stat(&self) -> &S {
         &self.stat
     }
 
+    /// Check whether `item` is in the cache.
+    ///
+    /// This is only a membership query, so it doesn't count as a use of the item; see
+    /// [`Cache::peek`].
+    ///
+    /// ```
+    /// use cache_sim::{Cache, Lru};
+    ///
+    /// let mut c = Cache::<Lru>::new(3);
+    /// c.access(0);
+    ///
+    /// assert!(c.contains(0));
+    /// assert!(!c.contains(1));
+    /// ```
+    pub fn contains(&self, item: I) -> bool {
+        self.set.contains(&item)
+    }
+
+    /// Check whether `item` is in the cache, without telling the replacement policy.
+    ///
+    /// Unlike [`Cache::access`], this doesn't update the replacement policy's state or the
+    /// statistic, and it never inserts the item, so peeking at an item doesn't affect what gets
+    /// evicted. This is useful for simulating existence checks that shouldn't count as a use.
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// use cache_sim::{Cache, Lru};
+    ///
+    /// let mut c = Cache::<Lru>::new(2);
+    /// c.access(0);
+    /// c.access(1);
+    ///
+    /// // peeking doesn't make 0 the most recently used item, so it's still evicted next
+    /// assert!(c.peek(0));
+    /// c.access(2);
+    /// assert_eq!(c.set(), &HashSet::from([1, 2]));
+    ///
+    /// // but accessing it does
+    /// c.access(1);
+    /// c.access(3);
+    /// assert_eq!(c.set(), &HashSet::from([1, 3]));
+    /// ```
+    pub fn peek(&self, item: I) -> bool {
+        self.contains(item)
+    }
+
+    /// Iterate over the items in the cache, in the replacement policy's order.
+    ///
+    /// For most policies which keep an order, this starts with the item which would be evicted
+    /// next; see [`ReplacementPolicy::order`]. Any items the policy doesn't order come last, in
+    /// arbitrary order.
+    ///
+    /// ```
+    /// use cache_sim::{Cache, Fifo, Lru, Trace};
+    ///
+    /// let t = Trace::from(vec![0, 1, 2, 0]);
+    ///
+    /// let mut lru = Cache::<Lru>::new(3);
+    /// lru.run_trace(&t);
+    /// assert_eq!(lru.ordered_iter().copied().collect::<Vec<_>>(), vec![1, 2, 0]);
+    ///
+    /// let mut fifo = Cache::<Fifo>::new(3);
+    /// fifo.run_trace(&t);
+    /// assert_eq!(fifo.ordered_iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    pub fn ordered_iter(&self) -> impl Iterator<Item = &I> {
+        let order = self.replacement_policy.order();
+        let ordered = order.iter().copied().collect::<HashSet<_>>();
+
+        order
+            .into_iter()
+            .filter_map(|i| self.set.get(&i))
+            .chain(self.set.iter().filter(move |i| !ordered.contains(i)))
+    }
+
     /// Get a reference to cache's set of items.
     pub const fn set(&self) -> &HashSet<I> {
         &self.set
     }
 }
 
-impl<R: ReplacementPolicy<I> + Default, S: Stat<I>, I: Item> Cache<R, S, I> {
+impl<R: ReplacementPolicy<I> + Default, S: Stat<I>, I: Item, A: AdmissionPolicy<I>>
+    Cache<R, S, I, A>
+{
     /// Create an empty cache using the default parameters for the replacement policy.
     #[must_use]
     pub fn new(capacity: u32) -> Self {
@@ -129,11 +633,154 @@ impl<R: ReplacementPolicy<I> + Default, S: Stat<I>, I: Item> Cache<R, S, I> {
             replacement_policy: R::default(),
             capacity,
             stat: S::default(),
+            admission_policy: A::default(),
+            observer: None,
         }
     }
+
+    /// Empty the cache, reset its replacement policy to its defaults, and reset its statistic and
+    /// admission policy. The capacity is unchanged, and so is the configuration of the statistic
+    /// and admission policy.
+    ///
+    /// ```
+    /// use cache_sim::{Cache, Lru, Trace};
+    /// use cache_sim::stats::{HitCount, Warmup};
+    ///
+    /// let t = Trace::from(vec![0, 1, 2, 0, 3, 0, 1]);
+    ///
+    /// let mut c = Cache::<Lru, HitCount>::new(3);
+    /// c.run_trace(&t);
+    /// let first = c.stat().0;
+    ///
+    /// c.clear();
+    /// c.run_trace(&t);
+    ///
+    /// assert_eq!(c.stat().0, first);
+    ///
+    /// // the warmup still applies after clearing
+    /// let mut c = Cache::<Lru, Warmup<HitCount>>::new(3).with_stat(Warmup::new(4));
+    /// c.run_trace(&t);
+    /// assert_eq!(c.stat().inner().0, 1);
+    ///
+    /// c.clear();
+    /// c.run_trace(&t);
+    /// assert_eq!(c.stat().inner().0, 1);
+    /// ```
+    pub fn clear(&mut self) {
+        self.clear_with_replacement_policy(R::default());
+    }
 }
 
-impl<R: ReplacementPolicy<u32>, S: Stat<u32>> Cache<R, S> {
+/// A builder for a [`Cache`], for when it needs a configured replacement policy, statistic, or
+/// admission policy.
+///
+/// Anything which isn't configured uses its defaults, but the capacity must be set.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{CacheBuilder, GeneralModelGenerator, GeneralModelItem, Landlord, Lfu, Lru};
+/// use cache_sim::stats::HitCount;
+///
+/// type Policy = Landlord<GeneralModelItem, Lfu<GeneralModelItem, Lru<GeneralModelItem>>>;
+///
+/// let mut c = CacheBuilder::<Policy, HitCount, _>::default()
+///     .capacity(2)
+///     .replacement_policy(Landlord::with_tiebreaker(Lfu::default(), 0.5))
+///     .build();
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let a = g.item(1.0, 1);
+/// let b = g.item(1.0, 1);
+/// let d = g.item(1.0, 1);
+///
+/// c.access(a);
+/// c.access(b);
+/// c.access(b);
+/// c.access(b);
+/// c.access(a);
+///
+/// // a and b are tied on credit, and a is more recent, but it's also less frequent
+/// c.access(d);
+/// assert_eq!(c.set(), &HashSet::from([b, d]));
+/// assert_eq!(c.stat().0, 3);
+/// ```
+#[must_use]
+pub struct CacheBuilder<
+    R: ReplacementPolicy<I>,
+    S: Stat<I> = (),
+    I: Item = u32,
+    A: AdmissionPolicy<I> = AdmitAll,
+> {
+    replacement_policy: R,
+    capacity: Option<u32>,
+    stat: S,
+    admission_policy: A,
+    _item: PhantomData<I>,
+}
+
+impl<R: ReplacementPolicy<I> + Default, S: Stat<I>, I: Item, A: AdmissionPolicy<I>> Default
+    for CacheBuilder<R, S, I, A>
+{
+    fn default() -> Self {
+        Self::with_replacement_policy(R::default())
+    }
+}
+
+impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item, A: AdmissionPolicy<I>> CacheBuilder<R, S, I, A> {
+    /// Start building a cache with an explicitly configured replacement policy, for policies
+    /// which don't have defaults.
+    pub fn with_replacement_policy(policy: R) -> Self {
+        Self {
+            replacement_policy: policy,
+            capacity: None,
+            stat: S::default(),
+            admission_policy: A::default(),
+            _item: PhantomData,
+        }
+    }
+
+    /// Set the capacity of the cache.
+    pub const fn capacity(mut self, capacity: u32) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Use an explicitly configured replacement policy.
+    pub fn replacement_policy(mut self, policy: R) -> Self {
+        self.replacement_policy = policy;
+        self
+    }
+
+    /// Use an explicitly configured statistic.
+    pub fn stat(mut self, stat: S) -> Self {
+        self.stat = stat;
+        self
+    }
+
+    /// Use an explicitly configured admission policy.
+    pub fn admission_policy(mut self, admission_policy: A) -> Self {
+        self.admission_policy = admission_policy;
+        self
+    }
+
+    /// Build the empty cache.
+    ///
+    /// # Panics
+    ///
+    /// If the capacity wasn't set.
+    pub fn build(self) -> Cache<R, S, I, A> {
+        Cache {
+            set: HashSet::default(),
+            replacement_policy: self.replacement_policy,
+            capacity: self.capacity.expect("The capacity must be set."),
+            stat: self.stat,
+            admission_policy: self.admission_policy,
+            observer: None,
+        }
+    }
+}
+
+impl<R: ReplacementPolicy<u32>, S: Stat<u32>, A: AdmissionPolicy<u32>> Cache<R, S, u32, A> {
     /// If the elements in the cache are all smaller than 26, display them as letters instead.
     ///
     /// ```
@@ -179,8 +826,7 @@ impl<R: ReplacementPolicy<u32>, S: Stat<u32>> Cache<R, S> {
                                        // itertools one when it's stabilized
     pub fn pretty_print(&self) -> String {
         if *self.set.iter().max().unwrap_or(&0) < 26 {
-            self.set
-                .iter()
+            self.ordered_iter()
                 .map(|i| {
                     // treat the number as an ascii value; adding the ascii value of A so we get
                     // capital letters
@@ -191,8 +837,7 @@ impl<R: ReplacementPolicy<u32>, S: Stat<u32>> Cache<R, S> {
                 .intersperse(", ".to_string())
                 .collect()
         } else {
-            self.set
-                .iter()
+            self.ordered_iter()
                 .map(u32::to_string)
                 .intersperse(", ".to_string())
                 .collect()
@@ -200,9 +845,96 @@ impl<R: ReplacementPolicy<u32>, S: Stat<u32>> Cache<R, S> {
     }
 }
 
-impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item> Display for Cache<R, S, I> {
+/// Run a cache with the default replacement policy over the trace, returning its statistic.
+///
+/// ```
+/// use cache_sim::{simulate, Lru, Trace};
+/// use cache_sim::stats::HitCount;
+///
+/// let trace = Trace::from(vec![0, 1, 2, 0, 3, 0, 1, 4, 0]);
+/// let hits: HitCount = simulate::<Lru, _, _>(&trace, 3);
+///
+/// assert_eq!(hits.0, 3);
+/// ```
+///
+/// # Panics
+///
+/// If the replacement policy errors, and so the cache ends up over capacity.
+#[must_use]
+pub fn simulate<R: ReplacementPolicy<I> + Default, S: Stat<I>, I: Item>(
+    trace: &Trace<I>,
+    capacity: u32,
+) -> S {
+    let mut cache = Cache::<R, S, I>::new(capacity);
+    cache.run_trace(trace);
+    cache.stat
+}
+
+/// Run caches with each of the replacement policies side by side over a single pass of the trace,
+/// returning the hit count of each one.
+///
+/// Every cache has the same capacity, and sees each access before the next one is read, so the
+/// results line up exactly with separate runs over the trace.
+///
+/// ```
+/// use cache_sim::{compare_policies, Cache, Fifo, Lfu, Lru, Trace};
+/// use cache_sim::replacement_policy::ReplacementPolicy;
+/// use cache_sim::stats::HitCount;
+///
+/// let trace = Trace::from(vec![0, 1, 2, 0, 3, 0, 1, 4, 0, 2, 1, 1, 3]);
+///
+/// let hits = compare_policies(
+///     &trace,
+///     3,
+///     vec![
+///         Box::new(Lru::default()),
+///         Box::new(Fifo::default()),
+///         Box::new(Lfu::<u32, Lru>::default()),
+///     ],
+/// );
+///
+/// fn alone<R: ReplacementPolicy<u32> + Default>(trace: &Trace<u32>) -> u32 {
+///     let mut c = Cache::<R, HitCount>::new(3);
+///     c.run_trace(trace);
+///     c.stat().0
+/// }
+///
+/// assert_eq!(hits[0].0, alone::<Lru>(&trace));
+/// assert_eq!(hits[1].0, alone::<Fifo>(&trace));
+/// assert_eq!(hits[2].0, alone::<Lfu>(&trace));
+/// ```
+///
+/// # Panics
+///
+/// If a replacement policy errors, and so its cache ends up over capacity.
+#[must_use]
+pub fn compare_policies<I: Item>(
+    trace: &Trace<I>,
+    capacity: u32,
+    policies: Vec<Box<dyn ReplacementPolicy<I>>>,
+) -> Vec<HitCount> {
+    let mut caches = policies
+        .into_iter()
+        .map(|policy| Cache::<_, HitCount, I>::with_replacement_policy(policy, capacity))
+        .collect::<Vec<_>>();
+
+    for item in trace {
+        for cache in &mut caches {
+            cache.access(*item);
+        }
+    }
+
+    caches
+        .into_iter()
+        .map(|cache| HitCount(cache.stat().0))
+        .collect()
+}
+
+impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item, A: AdmissionPolicy<I>> Display
+    for Cache<R, S, I, A>
+{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (i, item) in self.set.iter().enumerate() {
+        for (i, item) in self.ordered_iter().enumerate() {
             // prints the number associated with each item in the stack, in order
             if i == self.set.len() - 1 {
                 write!(f, "{}", item)?;
@@ -46,6 +46,172 @@ impl<I: Item> Condition<I> for LastNItems<I> {
     }
 }
 
+/// A condition on the strides leading up to an access, like [`LastNItems`] for strides.
+///
+/// The stride at an access is the difference between the [`Item::id`]s of the next item and it,
+/// so the last of the strides before an access is the one which reached it. This filters for
+/// accesses where the last N strides were the exact contents of the vector, which means the N + 1
+/// items up to and including it have to be in the trace.
+///
+/// ```
+/// use cache_sim::{condition::{Condition, LastNStrides}, Trace};
+///
+/// let condition = LastNStrides::new(vec![1, 1]);
+/// let trace = Trace::from(vec![4, 5, 6, 8]);
+/// assert!(!condition.check(&trace, 1)); // there's only one stride before 5
+/// assert!(condition.check(&trace, 2)); // 4 -> 5 -> 6
+/// assert!(!condition.check(&trace, 3)); // 5 -> 6 -> 8
+/// ```
+#[derive(Default, Debug)]
+pub struct LastNStrides {
+    strides: Vec<i64>,
+}
+
+impl LastNStrides {
+    #[must_use]
+    pub const fn new(strides: Vec<i64>) -> Self {
+        Self { strides }
+    }
+}
+
+impl<I: Item> Condition<I> for LastNStrides {
+    fn check(&self, trace: &Trace<I>, index: usize) -> bool {
+        if index >= self.strides.len() && index < trace.len() {
+            let start = index - self.strides.len();
+            self.strides.iter().enumerate().all(|(k, &stride)| {
+                i64::from(trace[start + k + 1].id()) - i64::from(trace[start + k].id()) == stride
+            })
+        } else {
+            false
+        }
+    }
+}
+
+/// A condition which only includes the first access to each item, i.e. the compulsory misses of
+/// any cache.
+///
+/// Checking an access scans every access before it, so building a histogram with this condition
+/// takes quadratic time in the length of the trace.
+///
+/// ```
+/// use cache_sim::{condition::{Condition, FirstAccessOnly}, Trace};
+///
+/// let trace = Trace::from(vec![0, 1, 0, 2]);
+/// let matches = (0..trace.len())
+///     .map(|i| FirstAccessOnly.check(&trace, i))
+///     .collect::<Vec<_>>();
+/// assert_eq!(matches, vec![true, true, false, true]);
+/// ```
+#[derive(Default, Debug)]
+pub struct FirstAccessOnly;
+
+impl<I: Item> Condition<I> for FirstAccessOnly {
+    fn check(&self, trace: &Trace<I>, index: usize) -> bool {
+        !trace[..index].contains(&trace[index])
+    }
+}
+
+/// A condition which only includes accesses to items at least as large as the given size.
+///
+/// ```
+/// use cache_sim::{condition::SizeAtLeast, Trace};
+///
+/// let trace = Trace::from_tuples([(0, 1.0, 1), (1, 1.0, 4), (0, 1.0, 1), (1, 1.0, 4)])?;
+/// let large = trace.frequency_histogram(&SizeAtLeast(4));
+/// assert_eq!(large.len(), 1);
+/// assert_eq!(large[&trace[1]], 2);
+/// # Ok::<(), std::num::TryFromIntError>(())
+/// ```
+#[derive(Default, Debug)]
+pub struct SizeAtLeast(pub u32);
+
+impl<I: Item> Condition<I> for SizeAtLeast {
+    fn check(&self, trace: &Trace<I>, index: usize) -> bool {
+        trace[index].size() >= self.0
+    }
+}
+
+/// A condition which only includes accesses to items which cost at least the given cost.
+#[derive(Default, Debug)]
+pub struct CostAtLeast(pub f64);
+
+impl<I: Item> Condition<I> for CostAtLeast {
+    fn check(&self, trace: &Trace<I>, index: usize) -> bool {
+        trace[index].cost() >= self.0
+    }
+}
+
+/// A condition which is true only if both conditions are.
+///
+/// ```
+/// use cache_sim::{condition::{And, Condition}, LastNItems, Trace};
+///
+/// // accesses to 2 which follow a 0
+/// let condition = And(LastNItems::new(vec![0]), |t: &Trace, i| t[i] == 2);
+/// let trace = Trace::from(vec![0, 2, 1, 2]);
+/// assert!(condition.check(&trace, 1));
+/// assert!(!condition.check(&trace, 3));
+/// ```
+#[derive(Default, Debug)]
+pub struct And<A, B>(pub A, pub B);
+
+impl<I: Item, A: Condition<I>, B: Condition<I>> Condition<I> for And<A, B> {
+    fn check(&self, trace: &Trace<I>, index: usize) -> bool {
+        self.0.check(trace, index) && self.1.check(trace, index)
+    }
+}
+
+/// A condition which is true if either condition is.
+#[derive(Default, Debug)]
+pub struct Or<A, B>(pub A, pub B);
+
+impl<I: Item, A: Condition<I>, B: Condition<I>> Condition<I> for Or<A, B> {
+    fn check(&self, trace: &Trace<I>, index: usize) -> bool {
+        self.0.check(trace, index) || self.1.check(trace, index)
+    }
+}
+
+/// A condition which is true exactly when the inner condition isn't.
+#[derive(Default, Debug)]
+pub struct Not<C>(pub C);
+
+impl<I: Item, C: Condition<I>> Condition<I> for Not<C> {
+    fn check(&self, trace: &Trace<I>, index: usize) -> bool {
+        !self.0.check(trace, index)
+    }
+}
+
+/// Methods for combining conditions, which are available on every condition.
+///
+/// ```
+/// use cache_sim::{condition::{Condition, ConditionExt}, LastNItems, Trace};
+///
+/// // accesses which don't follow a 0 or a 1
+/// let condition = LastNItems::new(vec![0]).or(LastNItems::new(vec![1])).not();
+/// let trace = Trace::from(vec![0, 1, 2, 3]);
+/// assert!(!condition.check(&trace, 1));
+/// assert!(!condition.check(&trace, 2));
+/// assert!(condition.check(&trace, 3));
+/// ```
+pub trait ConditionExt<I: Item>: Condition<I> + Sized {
+    /// A condition which is true only if both this and `other` are.
+    fn and<C: Condition<I>>(self, other: C) -> And<Self, C> {
+        And(self, other)
+    }
+
+    /// A condition which is true if either this or `other` is.
+    fn or<C: Condition<I>>(self, other: C) -> Or<Self, C> {
+        Or(self, other)
+    }
+
+    /// A condition which is true exactly when this isn't.
+    fn not(self) -> Not<Self> {
+        Not(self)
+    }
+}
+
+impl<I: Item, C: Condition<I>> ConditionExt<I> for C {}
+
 impl<I: Item, F: Fn(&Trace<I>, usize) -> bool> Condition<I> for F {
     fn check(&self, trace: &Trace<I>, index: usize) -> bool {
         self(trace, index)
@@ -120,4 +286,120 @@ mod tests {
         test_case!(repeated: 1; on 1, 2, 1, 0; 3 => true);
         test_case!(wrong_condition: 3; on 1, 2, 0, 1; 2 => false);
     }
+    mod last_n_strides_condition {
+        use super::*;
+
+        macro_rules! test_case {
+            ( $name:ident: $($seq:expr),*; on $($in:expr),*; $index:expr => $out:expr ) => {
+                #[test]
+                fn $name() {
+                    let condition = LastNStrides::new(vec![$($seq),*]);
+                    assert_eq!(condition.check(&Trace::from(vec![$($in),*]), $index), $out);
+                }
+            }
+        }
+
+        test_case!(one_up: 1; on 1, 0, 1; 2 => true);
+        test_case!(incrementing: 1, 1; on 0, 1, 2, 3; 3 => true);
+        test_case!(negative: -2, 3; on 5, 3, 6, 0; 2 => true);
+        test_case!(wrong_condition: 1; on 1, 2, 0, 1; 2 => false);
+        test_case!(not_enough_strides: 1, 1; on 0, 1, 2; 1 => false);
+        test_case!(past_the_end: 1; on 0, 1; 2 => false);
+        test_case!(no_strides: ; on 0, 1; 0 => true);
+    }
+
+    mod first_access_only {
+        use super::*;
+
+        use crate::condition::NoCondition;
+
+        #[test]
+        fn only_first_occurrences() {
+            let trace = Trace::from(vec![3, 3, 1, 3, 2, 1, 0]);
+            let matches = (0..trace.len())
+                .map(|i| FirstAccessOnly.check(&trace, i))
+                .collect::<Vec<_>>();
+
+            assert_eq!(matches, vec![true, false, true, false, true, false, true]);
+        }
+
+        #[test]
+        fn histogram_counts_each_item_once() {
+            let trace = Trace::from(vec![3, 3, 1, 3, 2, 1, 0]);
+            let firsts = trace.frequency_histogram(&FirstAccessOnly);
+            let all = trace.frequency_histogram(&NoCondition);
+
+            assert_eq!(firsts.len(), all.len());
+            assert!(firsts.values().all(|&f| f == 1));
+        }
+    }
+
+    mod item_properties {
+        use super::*;
+
+        use crate::condition::NoCondition;
+
+        fn trace() -> Trace<crate::GeneralModelItem> {
+            Trace::from_tuples([
+                (0, 1.0, 1),
+                (1, 5.0, 4),
+                (2, 2.0, 8),
+                (0, 1.0, 1),
+                (2, 2.0, 8),
+                (1, 5.0, 4),
+                (2, 2.0, 8),
+            ])
+            .expect("the ids fit in a u32")
+        }
+
+        #[test]
+        fn size_threshold() {
+            let trace = trace();
+            let all = trace.frequency_histogram(&NoCondition);
+            let large = trace.frequency_histogram(&SizeAtLeast(4));
+
+            // the large items keep all of their accesses, and the small one is gone
+            assert_eq!(large.len(), 2);
+            for (item, freq) in &large {
+                assert!(item.size() >= 4);
+                assert_eq!(all[item], *freq);
+            }
+            assert_eq!(large.values().sum::<u32>() + all[&trace[0]], 7);
+        }
+
+        #[test]
+        fn cost_threshold() {
+            let trace = trace();
+            let expensive = trace.frequency_histogram(&CostAtLeast(2.0));
+
+            assert_eq!(expensive.get(&trace[0]), None);
+            assert_eq!(expensive[&trace[1]], 2);
+            assert_eq!(expensive[&trace[2]], 3);
+        }
+    }
+
+    mod combinators {
+        use super::*;
+
+        macro_rules! test_case {
+            ( $name:ident: $condition:expr, on $($in:expr),* => $($out:expr),* ) => {
+                #[test]
+                fn $name() {
+                    let trace = Trace::from(vec![$($in),*]);
+                    let condition = $condition;
+                    let matches = (0..trace.len())
+                        .map(|i| condition.check(&trace, i))
+                        .collect::<Vec<_>>();
+                    assert_eq!(matches, vec![$($out),*]);
+                }
+            }
+        }
+
+        test_case!(and_no_condition: And(NoCondition, LastNItems::new(vec![1])), on 1, 1, 0 => false, true, true);
+        test_case!(and_closure: And(LastNItems::new(vec![0]), |t: &Trace<_>, i| t[i] == 2), on 0, 2, 0, 1, 2 => false, true, false, false, false);
+        test_case!(or_closure: Or(LastNItems::new(vec![0]), |t: &Trace<_>, i| t[i] == 2), on 0, 2, 3, 2 => false, true, false, true);
+        test_case!(not_no_condition: Not(NoCondition), on 0, 1 => false, false);
+        test_case!(not_last_n: Not(LastNItems::new(vec![0])), on 0, 1, 0, 0 => true, false, true, false);
+        test_case!(ext_methods: NoCondition.and(LastNItems::new(vec![0]).not()).or(|t: &Trace<_>, i| t[i] == 5), on 0, 0, 5, 1 => true, false, true, true);
+    }
 }
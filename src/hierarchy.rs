@@ -0,0 +1,178 @@
+//! A two-level cache hierarchy.
+
+use std::collections::HashSet;
+
+use crate::cache::{AccessOutcome, Cache};
+use crate::item::Item;
+use crate::replacement_policy::ReplacementPolicy;
+use crate::stats::{HitCount, Stat};
+use crate::trace::Trace;
+
+/// How the contents of the two levels of a [`Hierarchy`] relate to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inclusion {
+    /// Everything in L1 is also in L2. Missed items are inserted into both levels, and when L2
+    /// evicts an item, it is also invalidated from L1.
+    Inclusive,
+    /// Nothing in L1 is also in L2. Missed items are only inserted into L1, items evicted from L1
+    /// are inserted into L2, and items which hit in L2 move to L1.
+    Exclusive,
+    /// Non-inclusive non-exclusive. Missed items are inserted into both levels, but the levels
+    /// evict independently of each other.
+    Nine,
+}
+
+/// An L1 cache backed by an L2 cache.
+///
+/// Every access goes to L1, and only L1 misses go to L2. Each level counts its own hits, so the
+/// L2 hit count is out of the number of L1 misses.
+///
+/// ```
+/// use cache_sim::{Hierarchy, Inclusion, Lru, Trace};
+///
+/// let mut h = Hierarchy::<Lru, Lru>::new(1, 2, Inclusion::Nine);
+/// h.run_trace(&Trace::from(vec![0, 1, 0, 0, 1]));
+///
+/// assert_eq!(h.l1_stat().0, 1);
+/// assert_eq!(h.l2_stat().0, 2);
+/// ```
+pub struct Hierarchy<R1: ReplacementPolicy<I>, R2: ReplacementPolicy<I>, I: Item = u32> {
+    l1: Cache<R1, (), I>,
+    l2: Cache<R2, (), I>,
+    inclusion: Inclusion,
+    l1_stat: HitCount,
+    l2_stat: HitCount,
+}
+
+impl<R1: ReplacementPolicy<I>, R2: ReplacementPolicy<I>, I: Item> Hierarchy<R1, R2, I> {
+    /// Create a hierarchy out of two explicitly configured caches.
+    pub fn with_caches(l1: Cache<R1, (), I>, l2: Cache<R2, (), I>, inclusion: Inclusion) -> Self {
+        Self {
+            l1,
+            l2,
+            inclusion,
+            l1_stat: HitCount::default(),
+            l2_stat: HitCount::default(),
+        }
+    }
+
+    /// Update the hierarchy after an access to item.
+    ///
+    /// # Panics
+    ///
+    /// If either replacement policy errors, and so a level ends up over capacity.
+    pub fn access(&mut self, item: I) {
+        self.l1_stat.update(self.l1.set(), item, &HashSet::new());
+        if self.l1.contains(item) {
+            self.l1.access(item);
+            return;
+        }
+
+        self.l2_stat.update(self.l2.set(), item, &HashSet::new());
+        match self.inclusion {
+            Inclusion::Inclusive => {
+                if let AccessOutcome::Miss { evicted } = self.l2.access_outcome(item) {
+                    // keep L1 a subset of L2
+                    for evicted in evicted {
+                        self.l1.invalidate(evicted);
+                    }
+                }
+                self.l1.access(item);
+            }
+            Inclusion::Exclusive => {
+                self.l2.invalidate(item);
+                if let AccessOutcome::Miss { evicted } = self.l1.access_outcome(item) {
+                    for evicted in evicted {
+                        self.l2.access(evicted);
+                    }
+                }
+            }
+            Inclusion::Nine => {
+                self.l2.access(item);
+                self.l1.access(item);
+            }
+        }
+    }
+
+    /// Update the hierarchy after accessing all items in the trace.
+    pub fn run_trace(&mut self, trace: &Trace<I>) {
+        for item in trace {
+            self.access(*item);
+        }
+    }
+
+    /// Get a reference to the L1 cache.
+    pub const fn l1(&self) -> &Cache<R1, (), I> {
+        &self.l1
+    }
+
+    /// Get a reference to the L2 cache.
+    pub const fn l2(&self) -> &Cache<R2, (), I> {
+        &self.l2
+    }
+
+    /// Get the number of hits in L1.
+    pub const fn l1_stat(&self) -> &HitCount {
+        &self.l1_stat
+    }
+
+    /// Get the number of hits in L2, which only sees the accesses that missed in L1.
+    pub const fn l2_stat(&self) -> &HitCount {
+        &self.l2_stat
+    }
+}
+
+impl<R1: ReplacementPolicy<I> + Default, R2: ReplacementPolicy<I> + Default, I: Item>
+    Hierarchy<R1, R2, I>
+{
+    /// Create an empty hierarchy using the default parameters for both replacement policies.
+    #[must_use]
+    pub fn new(l1_capacity: u32, l2_capacity: u32, inclusion: Inclusion) -> Self {
+        Self::with_caches(Cache::new(l1_capacity), Cache::new(l2_capacity), inclusion)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lru;
+
+    /// Run a hierarchy of two LRU caches with capacity two over the trace.
+    fn run(inclusion: Inclusion, trace: Vec<u32>) -> Hierarchy<Lru, Lru> {
+        let mut h = Hierarchy::new(2, 2, inclusion);
+        h.run_trace(&Trace::from(trace));
+        h
+    }
+
+    #[test]
+    fn inclusive_back_invalidates() {
+        // L2 never sees the hits on 0, so it evicts 0, which also removes it from L1
+        let h = run(Inclusion::Inclusive, vec![0, 1, 0, 0, 2]);
+
+        assert_eq!(h.l1().set(), &HashSet::from([1, 2]));
+        assert_eq!(h.l2().set(), &HashSet::from([1, 2]));
+        assert_eq!(h.l1_stat().0, 2);
+        assert_eq!(h.l2_stat().0, 0);
+    }
+
+    #[test]
+    fn nine_evicts_independently() {
+        // L2 still evicts 0, but L1 keeps it
+        let h = run(Inclusion::Nine, vec![0, 1, 0, 0, 2, 0]);
+
+        assert_eq!(h.l1().set(), &HashSet::from([0, 2]));
+        assert_eq!(h.l2().set(), &HashSet::from([1, 2]));
+        assert_eq!(h.l1_stat().0, 3);
+    }
+
+    #[test]
+    fn exclusive_levels_are_disjoint() {
+        // 1 is evicted from L1 into L2, then hits in L2 and moves back, pushing 0 into L2
+        let h = run(Inclusion::Exclusive, vec![0, 1, 0, 0, 2, 1]);
+
+        assert_eq!(h.l1().set(), &HashSet::from([1, 2]));
+        assert_eq!(h.l2().set(), &HashSet::from([0]));
+        assert_eq!(h.l1_stat().0, 2);
+        assert_eq!(h.l2_stat().0, 1);
+    }
+}
@@ -1,17 +1,50 @@
 //! An abstracted cacheable item.
 
+use serde::{Deserialize, Serialize};
+
 /// Abstracts over a single item in a cache.
 pub trait Item:
     Default + std::fmt::Debug + std::fmt::Display + PartialEq + Eq + Copy + Clone + std::hash::Hash
 {
     /// The cost to cache the item; i.e. the cost of a miss.
+    ///
+    /// This doesn't have to be the same on every access to the item, so costs can vary over the
+    /// trace. Replacement policies see the cost of the current access, like [`Landlord`] resetting
+    /// an item's credit to its current cost when it's hit.
+    ///
+    /// [`Landlord`]: crate::Landlord
     fn cost(&self) -> f64;
 
     // TODO: should this be a float? the young paper says that sizes are integral, so we're making
     // them integers for now because floats work strangely, but maybe to be more general we should
     // allow floats
     /// The size of the item in the cache.
+    ///
+    /// This should be positive. A zero-size item always fits in the cache, and policies which
+    /// compare items by cost per unit size, like [`Landlord`](crate::Landlord) and
+    /// [`GreedyDualSize`](crate::GreedyDualSize), panic on them.
     fn size(&self) -> u32;
+
+    /// A numeric identifier for the item, like its address. This is used to map items to sets in
+    /// a set-associative cache.
+    ///
+    /// By default this is a hash of the item, which spreads items over sets well enough, but
+    /// doesn't mean anything as an address; items which have one, like trace addresses, should
+    /// override this so that strides between them make sense.
+    fn id(&self) -> u32 {
+        use std::hash::Hasher;
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.hash(&mut hasher);
+        #[allow(clippy::cast_possible_truncation)] // we only need some bits of the hash
+        let id = hasher.finish() as u32;
+        id
+    }
+
+    /// Whether this access to the item is a write. By default, every access is a read.
+    fn is_write(&self) -> bool {
+        false
+    }
 }
 
 impl Item for u32 {
@@ -21,6 +54,9 @@ impl Item for u32 {
     fn size(&self) -> u32 {
         1
     }
+    fn id(&self) -> u32 {
+        *self
+    }
 }
 
 /// A cacheable item with arbitrary const cost and size.
@@ -30,11 +66,13 @@ impl Item for u32 {
 /// _make sure_ that the identifier is different for each item in your trace, or else the trace
 /// will not work correctly.
 #[allow(clippy::module_name_repetitions)]
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Default, Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct GeneralModelItem {
     uid: u32,
     cost: f64,
     size: u32,
+    #[serde(default)]
+    write: bool,
 }
 
 impl GeneralModelItem {
@@ -43,7 +81,43 @@ impl GeneralModelItem {
     /// If you don't care about the unique identifier, prefer using a [`GeneralModelGenerator`].
     #[must_use]
     pub const fn new(uid: u32, cost: f64, size: u32) -> Self {
-        Self { uid, cost, size }
+        Self {
+            uid,
+            cost,
+            size,
+            write: false,
+        }
+    }
+
+    /// Change the cost of this access to the item, e.g. to model a cost that changes over the
+    /// trace.
+    ///
+    /// Like the size, this isn't part of the item's identity, so the item is still equal to its
+    /// other accesses.
+    ///
+    /// ```
+    /// use cache_sim::GeneralModelItem;
+    /// use cache_sim::item::Item;
+    ///
+    /// let item = GeneralModelItem::new(0, 1.0, 1);
+    /// let later = item.with_cost(10.0);
+    /// assert_eq!(later.cost(), 10.0);
+    /// assert_eq!(later, item);
+    /// ```
+    #[must_use]
+    pub const fn with_cost(mut self, cost: f64) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// Make this access to the item a write.
+    ///
+    /// Like the cost and size, this isn't part of the item's identity, so a write to an item is
+    /// equal to a read of it.
+    #[must_use]
+    pub const fn as_write(mut self) -> Self {
+        self.write = true;
+        self
     }
 }
 
@@ -84,6 +158,132 @@ impl Item for GeneralModelItem {
     fn size(&self) -> u32 {
         self.size
     }
+
+    fn id(&self) -> u32 {
+        self.uid
+    }
+
+    fn is_write(&self) -> bool {
+        self.write
+    }
+}
+
+/// A cacheable item with several costs, like the cost columns of an `atf` trace.
+///
+/// One of the costs is the primary cost, which is the one returned by [`Item::cost`], and so the
+/// one that replacement policies like [`Landlord`](crate::Landlord) optimize; the others can still
+/// be read with [`Self::cost_at`], e.g. to report the total miss cost by a different measure.
+///
+/// The number of costs is fixed, so that the item can still be `Copy`. Like
+/// [`GeneralModelItem`], the item's identity is only its identifier.
+///
+/// ```
+/// use cache_sim::item::{Item, MultiCostItem};
+///
+/// let item = MultiCostItem::new(0, [7.0, 2.5], 1);
+/// assert_eq!(item.cost(), 7.0);
+/// assert_eq!(item.cost_at(1), Some(2.5));
+/// assert_eq!(item.cost_at(2), None);
+///
+/// let item = item.with_primary(1);
+/// assert_eq!(item.cost(), 2.5);
+/// assert_eq!(item.costs(), &[7.0, 2.5]);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct MultiCostItem<const N: usize> {
+    uid: u32,
+    costs: [f64; N],
+    primary: usize,
+    size: u32,
+}
+
+impl<const N: usize> MultiCostItem<N> {
+    /// Create a new item, whose primary cost is the first one.
+    ///
+    /// # Panics
+    ///
+    /// If there are no costs.
+    #[must_use]
+    pub const fn new(uid: u32, costs: [f64; N], size: u32) -> Self {
+        assert!(N > 0, "There must be at least one cost.");
+
+        Self {
+            uid,
+            costs,
+            primary: 0,
+            size,
+        }
+    }
+
+    /// Make the cost with the given index the primary cost.
+    ///
+    /// # Panics
+    ///
+    /// If there is no cost with that index.
+    #[must_use]
+    pub const fn with_primary(mut self, primary: usize) -> Self {
+        assert!(primary < N, "The primary cost must be one of the costs.");
+
+        self.primary = primary;
+        self
+    }
+
+    /// Get the cost with the given index, if there is one.
+    #[must_use]
+    pub fn cost_at(&self, index: usize) -> Option<f64> {
+        self.costs.get(index).copied()
+    }
+
+    /// Get all of the costs.
+    #[must_use]
+    pub const fn costs(&self) -> &[f64; N] {
+        &self.costs
+    }
+}
+
+impl<const N: usize> Default for MultiCostItem<N> {
+    fn default() -> Self {
+        Self {
+            uid: 0,
+            costs: [0.0; N],
+            primary: 0,
+            size: 0,
+        }
+    }
+}
+
+impl<const N: usize> std::hash::Hash for MultiCostItem<N> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.uid.hash(state);
+    }
+}
+
+impl<const N: usize> std::cmp::PartialEq for MultiCostItem<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.uid == other.uid
+    }
+}
+
+impl<const N: usize> std::cmp::Eq for MultiCostItem<N> {}
+
+impl<const N: usize> std::fmt::Display for MultiCostItem<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.uid)
+    }
+}
+
+impl<const N: usize> Item for MultiCostItem<N> {
+    fn cost(&self) -> f64 {
+        self.costs[self.primary]
+    }
+
+    fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn id(&self) -> u32 {
+        self.uid
+    }
 }
 
 /// A generator for general model items.
@@ -94,17 +294,54 @@ pub struct GeneralModelGenerator {
     counter: u32,
 }
 
+/// The error returned by [`GeneralModelGenerator::try_item`] for an item with size zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroSizeError;
+
+impl std::fmt::Display for ZeroSizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "items must have a positive size")
+    }
+}
+
+impl std::error::Error for ZeroSizeError {}
+
 impl GeneralModelGenerator {
     pub fn item(&mut self, cost: f64, size: u32) -> GeneralModelItem {
         let ret = GeneralModelItem {
             uid: self.counter,
             cost,
             size,
+            write: false,
         };
         self.counter += 1;
         ret
     }
 
+    /// Create a new item, unless its size is zero.
+    ///
+    /// No identifier is used up by a rejected item.
+    ///
+    /// ```
+    /// use cache_sim::GeneralModelGenerator;
+    /// use cache_sim::item::{Item, ZeroSizeError};
+    ///
+    /// let mut g = GeneralModelGenerator::new();
+    /// assert_eq!(g.try_item(1.0, 0), Err(ZeroSizeError));
+    /// assert_eq!(g.try_item(1.0, 1).map(|i| i.id()), Ok(0));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If `size` is zero.
+    pub fn try_item(&mut self, cost: f64, size: u32) -> Result<GeneralModelItem, ZeroSizeError> {
+        if size == 0 {
+            Err(ZeroSizeError)
+        } else {
+            Ok(self.item(cost, size))
+        }
+    }
+
     /// Create a new general model item generator.
     #[must_use]
     pub fn new() -> Self {
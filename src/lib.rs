@@ -1,17 +1,28 @@
 #![doc = include_str!("../README.md")]
 
+pub mod admission;
 pub mod atf;
 mod cache;
 pub mod condition;
+mod hierarchy;
 pub mod item;
 pub mod output;
 pub mod replacement_policy;
+mod set_associative;
 pub mod stats;
 pub mod trace;
+mod ttl;
 
-pub use cache::Cache;
+pub use cache::{compare_policies, simulate, AccessOutcome, Cache, CacheBuilder};
 pub use condition::{LastNItems, NoCondition};
+pub use hierarchy::{Hierarchy, Inclusion};
 pub use item::{GeneralModelGenerator, GeneralModelItem};
+pub use set_associative::SetAssociative;
 pub use trace::Trace;
+pub use ttl::TtlCache;
 
-pub use replacement_policy::{Fifo, Landlord, Lfu, Lru, Mru, Rand};
+pub use replacement_policy::{
+    Arc, Belady, Clock, ClockPro, EvictCheapest, EvictLargest, Fifo, FifoReinsertion,
+    GreedyDualSize, GreedyDualSizeFrequency, Hyperbolic, Landlord, Lfu, LfuAging, Lirs, Lru, LruK,
+    Mru, MultiQueue, Rand, S3Fifo, SampledLru, Slru, TwoQ, WTinyLfu,
+};
@@ -60,6 +60,68 @@ pub fn to_csv(
     wtr.serialize(output)
 }
 
+/// Write a set of statistics to a csv, preceded by a header row naming each column.
+///
+/// The columns are the name, then each stat, labelled by `stat_labels`, then the number of
+/// infinite stack distances, and then the frequency of each stack distance from zero up to the
+/// largest one in the trace, labelled `distance:N`.
+///
+/// ```
+/// # fn main() -> Result<(), csv::Error> {
+/// use cache_sim::output::to_csv_with_header;
+/// use cache_sim::Trace;
+///
+/// let distances = Trace::from(vec![0, 0, 1, 0]).stack_distances();
+///
+/// let mut out = Vec::new();
+/// to_csv_with_header("Lru", &["hits", "misses"], &[1, 3], &distances, &mut out)?;
+///
+/// let out = String::from_utf8(out).unwrap();
+/// let mut lines = out.lines();
+/// assert_eq!(lines.next(), Some("Name,hits,misses,infinities,distance:0,distance:1"));
+/// assert_eq!(lines.next(), Some("Lru,1,3,2,1,1"));
+/// assert_eq!(lines.next(), None);
+/// # Ok(())}
+/// ```
+///
+/// # Errors
+/// If writing fails.
+///
+/// # Panics
+/// If there isn't exactly one label for each stat.
+pub fn to_csv_with_header<W: Write>(
+    name: &str,
+    stat_labels: &[&str],
+    stats: &[u32],
+    stack_distances: &StackDistance,
+    writer: W,
+) -> Result<(), csv::Error> {
+    assert_eq!(
+        stat_labels.len(),
+        stats.len(),
+        "There must be one label for each stat."
+    );
+
+    let (stack_distances, infinities) = stack_distances.histogram();
+
+    let mut labels = vec![String::from("Name")];
+    labels.extend(stat_labels.iter().map(|&label| String::from(label)));
+    labels.push(String::from("infinities"));
+    labels.extend((0..stack_distances.len()).map(|distance| format!("distance:{distance}")));
+
+    let output = OutputCsvRow {
+        name,
+        stats,
+        stack_distances: &stack_distances,
+        infinities,
+    };
+
+    // one writer for both rows, so it checks that they have the same number of fields
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.serialize(HeaderRow { labels: &labels })?;
+    wtr.serialize(output)
+}
+
 struct FreqHistRow<'a, I: Item, H: std::hash::BuildHasher> {
     // TODO: does this need to be owned
     name: &'a str,
@@ -115,6 +177,74 @@ pub fn histogram_out<I: Item, W: Write, H: std::hash::BuildHasher>(
     wtr.serialize(output)
 }
 
+struct MrcRow<'a> {
+    name: &'a str,
+    mrc: &'a [f64],
+}
+
+impl Serialize for MrcRow<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(1 + self.mrc.len()))?;
+
+        seq.serialize_element(self.name)?;
+        for ratio in self.mrc {
+            seq.serialize_element(ratio)?;
+        }
+        seq.end()
+    }
+}
+
+/// Write a miss ratio curve, like the one from [`StackDistance::miss_ratio_curve`], to a row of a
+/// csv file.
+///
+/// The row is the name, followed by the miss ratio of each cache size, starting from zero.
+///
+/// ```
+/// # fn main() -> Result<(), csv::Error> {
+/// use cache_sim::output::mrc_to_csv;
+///
+/// let mut out = Vec::new();
+/// mrc_to_csv("Lru", &[1.0, 0.75, 0.5], &mut out)?;
+///
+/// let out = String::from_utf8(out).unwrap();
+/// assert_eq!(out, "Lru,1.0,0.75,0.5\n");
+/// assert_eq!(out.trim_end().split(',').count(), 4);
+/// # Ok(())}
+/// ```
+///
+/// # Errors
+/// If the writing fails.
+pub fn mrc_to_csv<W: Write>(name: &str, mrc: &[f64], writer: W) -> Result<(), csv::Error> {
+    let mut wtr = csv::Writer::from_writer(writer);
+
+    wtr.serialize(MrcRow { name, mrc })
+}
+
+/// Get the frequencies of a histogram as pairs of items and frequencies, sorted by
+/// [`Item::id`], so that the order doesn't depend on the histogram's hasher.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// use cache_sim::output::histogram_to_pairs;
+///
+/// let histogram = HashMap::from([(3, 1), (0, 4), (7, 2)]);
+/// assert_eq!(histogram_to_pairs(&histogram), vec![(0, 4), (3, 1), (7, 2)]);
+/// ```
+#[must_use]
+pub fn histogram_to_pairs<I: Item, H: std::hash::BuildHasher>(
+    histogram: &HashMap<I, u32, H>,
+) -> Vec<(I, u32)> {
+    let mut pairs = histogram
+        .iter()
+        .map(|(&item, &freq)| (item, freq))
+        .collect::<Vec<_>>();
+    pairs.sort_by_key(|(item, _)| item.id());
+    pairs
+}
+
 struct HeaderRow<'a> {
     labels: &'a [String],
 }
@@ -1,10 +1,15 @@
 //! Implementations of cache replacement policies.
 
 use crate::item::{GeneralModelItem, Item};
+use crate::trace::Trace;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 
 use approx::abs_diff_eq;
-use rand::seq::IteratorRandom;
+use rand::rngs::{StdRng, ThreadRng};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 
 /// An abstracted cache replacement policy.
 pub trait ReplacementPolicy<I: Item> {
@@ -13,6 +18,92 @@ pub trait ReplacementPolicy<I: Item> {
 
     /// Return the item to be evicted. This should _not_ be `next`.
     fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I>;
+
+    /// Return the items to be evicted so that the set fits in `capacity`, without any incoming
+    /// item. This is used when the cache shrinks.
+    ///
+    /// By default this evicts items from the front of [`ReplacementPolicy::order`], and then any
+    /// items the policy doesn't order, calling [`ReplacementPolicy::remove`] on each of them.
+    /// Policies which keep an order should usually override this, to evict by their own state.
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        let ordered = self.order().into_iter().filter(|i| set.contains(i));
+        for item in ordered.chain(hash_order(set)) {
+            if remaining_size(set, &to_evict) <= capacity {
+                break;
+            }
+            to_evict.insert(item);
+        }
+
+        for &item in &to_evict {
+            self.remove(item);
+        }
+
+        to_evict
+    }
+
+    /// Forget an item which was removed from the cache without the policy evicting it.
+    ///
+    /// By default this does nothing, which is fine for policies which don't keep state about
+    /// resident items.
+    fn remove(&mut self, _item: I) {}
+
+    /// Be told about a miss on `item` which wasn't inserted into the cache, say because the
+    /// admission policy didn't admit it. Nothing is evicted.
+    ///
+    /// By default this does nothing, which is fine for policies which only keep state about
+    /// resident items.
+    fn bypass(&mut self, _item: I) {}
+
+    /// The items the policy is tracking, in its own order. For policies which keep an order of
+    /// resident items, this should start with the item which would be evicted next.
+    ///
+    /// Items which aren't in the cache are ignored. By default this is empty, which means the
+    /// policy doesn't keep an order.
+    fn order(&self) -> Vec<I> {
+        Vec::new()
+    }
+
+    /// Return the items that [`ReplacementPolicy::replace`] would evict, without changing the
+    /// policy's state.
+    ///
+    /// By default this runs `replace` on a copy of the policy, which is always right, but can be
+    /// slow for policies with a lot of state.
+    fn would_replace(&self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I>
+    where
+        Self: Clone,
+    {
+        self.clone().replace(set, capacity, next)
+    }
+}
+
+// this lets caches hold type-erased policies, so that different policies can be run side by side;
+// see `compare_policies` for a motivating example.
+impl<I: Item> ReplacementPolicy<I> for Box<dyn ReplacementPolicy<I>> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        self.as_mut().update_state(set, capacity, next);
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        self.as_mut().replace(set, capacity, next)
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        self.as_mut().shrink(set, capacity)
+    }
+
+    fn remove(&mut self, item: I) {
+        self.as_mut().remove(item);
+    }
+
+    fn bypass(&mut self, item: I) {
+        self.as_mut().bypass(item);
+    }
+
+    fn order(&self) -> Vec<I> {
+        self.as_ref().order()
+    }
 }
 
 pub trait Tiebreaker<I: Item>: ReplacementPolicy<I> {
@@ -20,6 +111,28 @@ pub trait Tiebreaker<I: Item>: ReplacementPolicy<I> {
     fn tiebreak(&mut self, from: &HashSet<I>, size_to_free: u32) -> HashSet<I>;
 }
 
+/// The total size of the items in the set which aren't being evicted.
+fn remaining_size<I: Item>(set: &HashSet<I>, to_evict: &HashSet<I>) -> u32 {
+    set.iter()
+        .filter(|i| !to_evict.contains(*i))
+        .map(Item::size)
+        .sum()
+}
+
+/// The items of the set, in an order which doesn't depend on the set's iteration order.
+///
+/// The iteration order of a `HashSet` is itself random, so policies which pick items with an RNG
+/// sort by a fixed hash first, to make the choice depend only on the RNG.
+fn hash_order<I: Item>(set: &HashSet<I>) -> Vec<I> {
+    let mut candidates = set.iter().copied().collect::<Vec<_>>();
+    candidates.sort_by_cached_key(|i| {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        i.hash(&mut hasher);
+        hasher.finish()
+    });
+    candidates
+}
+
 /// The LRU replacement policy, which evicts the least recently used item.
 ///
 /// ```
@@ -36,7 +149,7 @@ pub trait Tiebreaker<I: Item>: ReplacementPolicy<I> {
 ///
 /// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
 /// ```
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Lru<I: Item = u32> {
     stack: Vec<I>,
 }
@@ -54,6 +167,33 @@ impl<I: Item> ReplacementPolicy<I> for Lru<I> {
         self.update_state(set, capacity, next);
         HashSet::from([self.stack.remove(0)])
     }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            to_evict.insert(self.stack.remove(0));
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.stack.retain(|&i| i != item);
+    }
+
+    fn order(&self) -> Vec<I> {
+        self.stack.clone()
+    }
+
+    fn would_replace(&self, _: &HashSet<I>, _: u32, next: I) -> HashSet<I> {
+        self.stack
+            .iter()
+            .copied()
+            .filter(|&i| i != next)
+            .take(1)
+            .collect()
+    }
 }
 
 impl<I: Item> Tiebreaker<I> for Lru<I> {
@@ -90,7 +230,7 @@ impl<I: Item> Tiebreaker<I> for Lru<I> {
 ///
 /// assert_eq!(c.set(), &HashSet::from([1, 2, 3]));
 /// ```
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Fifo<I: Item = u32> {
     stack: VecDeque<I>,
 }
@@ -106,23 +246,280 @@ impl<I: Item> ReplacementPolicy<I> for Fifo<I> {
         self.update_state(set, capacity, next);
         HashSet::from([self.stack.pop_front().expect("The cache is non-empty.")])
     }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            to_evict.insert(self.stack.pop_front().expect("The cache is non-empty."));
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.stack.retain(|&i| i != item);
+    }
+
+    fn order(&self) -> Vec<I> {
+        self.stack.iter().copied().collect()
+    }
+
+    fn would_replace(&self, _: &HashSet<I>, _: u32, next: I) -> HashSet<I> {
+        self.stack
+            .iter()
+            .copied()
+            .filter(|&i| i != next)
+            .take(1)
+            .collect()
+    }
+}
+
+/// The FIFO-Reinsertion replacement policy, also known as second chance.
+///
+/// Items carry a visited bit, which is set when they're hit. When the item at the front of the
+/// queue is considered for eviction, if its bit is set, it is cleared and the item is moved to the
+/// back of the queue instead of being evicted. This is the same as [`Clock`], but over an explicit
+/// queue.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, FifoReinsertion};
+///
+/// let mut c = Cache::<FifoReinsertion>::new(3);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(0);
+/// c.access(3);
+///
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+#[derive(Default, Clone)]
+pub struct FifoReinsertion<I: Item = u32> {
+    queue: VecDeque<(I, bool)>,
+}
+
+impl<I: Item> ReplacementPolicy<I> for FifoReinsertion<I> {
+    fn update_state(&mut self, _: &HashSet<I>, _: u32, next: I) {
+        if let Some((_, visited)) = self.queue.iter_mut().find(|(i, _)| *i == next) {
+            *visited = true;
+        } else {
+            self.queue.push_back((next, false));
+        }
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.queue.push_back((next, false));
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            let (item, visited) = self.queue.pop_front().expect("The cache is non-empty.");
+            if visited {
+                // give the item a second chance
+                self.queue.push_back((item, false));
+            } else {
+                to_evict.insert(item);
+            }
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.queue.retain(|&(i, _)| i != item);
+    }
+
+    fn order(&self) -> Vec<I> {
+        self.queue.iter().map(|&(i, _)| i).collect()
+    }
 }
 
 /// The RAND replacement policy, which evicts a random item.
-#[derive(Default)]
-pub struct Rand {
-    rng: rand::rngs::ThreadRng,
+///
+/// This uses the thread-local RNG by default; use [`Rand::seeded`] or [`Rand::with_rng`] for
+/// reproducible simulations.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, Rand};
+///
+/// let mut a = Cache::<Rand<_>>::with_replacement_policy(Rand::seeded(0), 3_u32);
+/// let mut b = Cache::<Rand<_>>::with_replacement_policy(Rand::seeded(0), 3_u32);
+///
+/// for i in 0..10 {
+///     a.access(i);
+///     b.access(i);
+/// }
+///
+/// assert_eq!(a.set(), b.set());
+/// ```
+#[derive(Default, Clone)]
+pub struct Rand<R: Rng = ThreadRng> {
+    rng: R,
 }
 
-impl<I: Item> ReplacementPolicy<I> for Rand {
+impl<R: Rng> Rand<R> {
+    /// Instantiate a new RAND replacement policy, which draws from `rng`.
+    #[must_use]
+    pub const fn with_rng(rng: R) -> Self {
+        Self { rng }
+    }
+}
+
+impl Rand<StdRng> {
+    /// Instantiate a new RAND replacement policy, which draws from an RNG with a fixed `seed`.
+    #[must_use]
+    pub fn seeded(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<I: Item, R: Rng> ReplacementPolicy<I> for Rand<R> {
     fn update_state(&mut self, _: &HashSet<I>, _: u32, _: I) {}
 
     fn replace(&mut self, set: &HashSet<I>, _: u32, _: I) -> HashSet<I> {
-        HashSet::from([*set
-            .iter()
+        HashSet::from([*hash_order(set)
             .choose(&mut self.rng)
             .expect("The set is non-empty.")])
     }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut candidates = hash_order(set);
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            let index = self.rng.gen_range(0..candidates.len());
+            to_evict.insert(candidates.swap_remove(index));
+        }
+
+        to_evict
+    }
+}
+
+/// The sampled LRU replacement policy, which approximates LRU the way Redis does, by evicting the
+/// least recently used of a few randomly sampled items.
+///
+/// This only needs to remember when each item was last used, not an order of all of them. The
+/// more items are sampled, the closer it is to LRU; sampling at least the number of items in the
+/// cache is exactly LRU. Like [`Rand`], this uses the thread-local RNG by default; use
+/// [`SampledLru::seeded`] or [`SampledLru::with_rng`] for reproducible simulations.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, SampledLru};
+///
+/// let mut c = Cache::<SampledLru<_, _>>::with_replacement_policy(SampledLru::seeded(0, 3), 3_u32);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(0);
+/// c.access(3);
+///
+/// // every item is sampled, so this is the same as LRU
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+#[derive(Clone)]
+pub struct SampledLru<I: Item = u32, R: Rng = ThreadRng> {
+    last_access: HashMap<I, u64>,
+    clock: u64,
+    rng: R,
+    samples: usize,
+}
+
+impl<I: Item, R: Rng + Default> Default for SampledLru<I, R> {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+impl<I: Item, R: Rng + Default> SampledLru<I, R> {
+    /// Instantiate a new sampled LRU replacement policy, which samples `samples` items for each
+    /// eviction. This defaults to 5 samples, like Redis.
+    ///
+    /// # Panics
+    ///
+    /// If `samples` is zero.
+    #[must_use]
+    pub fn new(samples: usize) -> Self {
+        Self::with_rng(R::default(), samples)
+    }
+}
+
+impl<I: Item, R: Rng> SampledLru<I, R> {
+    /// Instantiate a new sampled LRU replacement policy, which draws its samples from `rng`.
+    ///
+    /// # Panics
+    ///
+    /// If `samples` is zero.
+    #[must_use]
+    pub fn with_rng(rng: R, samples: usize) -> Self {
+        assert!(samples > 0, "Sampled LRU needs at least one sample.");
+
+        Self {
+            last_access: HashMap::new(),
+            clock: 0,
+            rng,
+            samples,
+        }
+    }
+}
+
+impl<I: Item> SampledLru<I, StdRng> {
+    /// Instantiate a new sampled LRU replacement policy, which draws its samples from an RNG with
+    /// a fixed `seed`.
+    ///
+    /// # Panics
+    ///
+    /// If `samples` is zero.
+    #[must_use]
+    pub fn seeded(seed: u64, samples: usize) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed), samples)
+    }
+}
+
+impl<I: Item, R: Rng> ReplacementPolicy<I> for SampledLru<I, R> {
+    fn update_state(&mut self, _: &HashSet<I>, _: u32, next: I) {
+        self.last_access.insert(next, self.clock);
+        self.clock += 1;
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.update_state(set, capacity, next);
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut candidates = hash_order(set);
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            let victim = *candidates
+                .choose_multiple(&mut self.rng, self.samples)
+                .min_by_key(|i| self.last_access[*i])
+                .expect("The set is non-empty.");
+            candidates.retain(|&i| i != victim);
+            to_evict.insert(victim);
+        }
+
+        for item in &to_evict {
+            self.last_access.remove(item);
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.last_access.remove(&item);
+    }
 }
 
 /// The MRU replacement policy, which evicts the most recently used item.
@@ -140,7 +537,7 @@ impl<I: Item> ReplacementPolicy<I> for Rand {
 ///
 /// assert_eq!(c.set(), &HashSet::from([0, 1, 3]));
 /// ```
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Mru<I: Item = u32> {
     stack: Vec<I>,
 }
@@ -155,12 +552,44 @@ impl<I: Item> ReplacementPolicy<I> for Mru<I> {
     }
 
     fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        // next isn't on the stack yet, so the top of the stack is the most recently used item
+        // _other_ than it; this works even if that's the only item in the cache
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
         self.update_state(set, capacity, next);
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            to_evict.insert(self.stack.pop().expect("The cache is non-empty."));
+        }
 
-        // update_state just pushed the next item to the top of the stack, and we can't evict that
-        // item (we want the most recently used item _other_ than it), so we get the second-to-last
-        // item from the stack.
-        HashSet::from([self.stack.remove(self.stack.len() - 2)])
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.stack.retain(|&i| i != item);
+    }
+
+    fn order(&self) -> Vec<I> {
+        self.stack.iter().rev().copied().collect()
+    }
+
+    fn would_replace(&self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        // replace evicts from the top of the stack until next fits
+        let capacity = capacity.saturating_sub(next.size());
+        let mut to_evict = HashSet::new();
+
+        for &i in self.stack.iter().rev().filter(|&&i| i != next) {
+            if remaining_size(set, &to_evict) <= capacity {
+                break;
+            }
+            to_evict.insert(i);
+        }
+
+        to_evict
     }
 }
 
@@ -183,7 +612,7 @@ impl<I: Item> ReplacementPolicy<I> for Mru<I> {
 ///
 /// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
 /// ```
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Lfu<I: Item = u32, T: Tiebreaker<I> = Lru> {
     counts: HashMap<I, u32>,
     tiebreaker: T,
@@ -197,71 +626,239 @@ impl<I: Item, T: Tiebreaker<I>> ReplacementPolicy<I> for Lfu<I, T> {
 
     fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
         self.update_state(set, capacity, next);
-        let min = self
-            .counts
-            .iter()
-            .filter(|&(i, _)| set.contains(i)) // we have to evict something that's in the cache
-            .map(|(_, &count)| count)
-            .min()
-            .expect("The set is non-empty.");
+        least_frequent_evict(
+            &self.counts,
+            &mut self.tiebreaker,
+            set,
+            capacity.saturating_sub(next.size()),
+        )
+    }
 
-        self.tiebreaker.tiebreak(
-            &self
-                .counts
-                .iter()
-                .filter(|&(_, &count)| count == min)
-                .map(|(&i, _)| i)
-                .collect(),
-            1,
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        least_frequent_evict(&self.counts, &mut self.tiebreaker, set, capacity)
+    }
+
+    fn remove(&mut self, item: I) {
+        self.counts.remove(&item);
+        self.tiebreaker.remove(item);
+    }
+
+    fn bypass(&mut self, item: I) {
+        self.tiebreaker.bypass(item);
+    }
+}
+
+// as a tiebreaker, this evicts the least frequently used of the tied items, using its own
+// tiebreaker for the items which are tied on frequency too
+impl<I: Item, T: Tiebreaker<I>> Tiebreaker<I> for Lfu<I, T> {
+    fn tiebreak(&mut self, from: &HashSet<I>, size_to_free: u32) -> HashSet<I> {
+        let size = from.iter().map(Item::size).sum::<u32>();
+        least_frequent_evict(
+            &self.counts,
+            &mut self.tiebreaker,
+            from,
+            size.saturating_sub(size_to_free),
         )
     }
 }
 
-/// The landlord replacement algotihm.
-///
-/// Detailed in this paper: <https://arxiv.org/abs/cs/0205033>
+/// The LFU replacement policy with aging, which evicts the least frequently used item, but
+/// periodically decays every count so that items which were only hot a long time ago are forgotten.
 ///
-/// The tiebreaker (for evicting multiple zero-credit items) defaults to Lru.
+/// The tiebreaker defaults to Lru.
 ///
 /// ```
 /// # use std::collections::HashSet;
-/// use cache_sim::{Cache, Landlord, GeneralModelGenerator};
+/// use cache_sim::{Cache, LfuAging};
 ///
-/// let mut cache = Cache::<Landlord, (), _>::new(3);
-/// let mut g = GeneralModelGenerator::new();
-///
-/// let a = g.item(1.0, 1);
-/// let b = g.item(0.5, 2);
-/// let c = g.item(100.0, 2);
-/// let d = g.item(1.0, 1);
+/// let mut c = Cache::<LfuAging>::new(3);
 ///
-/// cache.access(a);
-/// cache.access(b);
-/// cache.access(c);
-/// cache.access(d);
+/// c.access(0);
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(2);
+/// c.access(3);
 ///
-/// assert_eq!(cache.set(), &HashSet::from([c, d]));
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
 /// ```
-pub struct Landlord<I: Item = GeneralModelItem, T: Tiebreaker<I> = Lru<GeneralModelItem>> {
-    credit: HashMap<I, f64>,
-    credit_increase: f64,
+#[derive(Clone)]
+pub struct LfuAging<I: Item = u32, T: Tiebreaker<I> = Lru> {
+    counts: HashMap<I, u32>,
     tiebreaker: T,
+    accesses: u32,
+    age_factor: f64,
+    age_interval: u32,
 }
 
-impl<I: Item, T: Tiebreaker<I> + Default> Default for Landlord<I, T> {
+impl<I: Item, T: Tiebreaker<I> + Default> Default for LfuAging<I, T> {
     fn default() -> Self {
-        Self {
-            credit: HashMap::default(),
-            credit_increase: 1.0,
-            tiebreaker: T::default(),
-        }
+        Self::new(0.5, 100)
     }
 }
 
-impl<I: Item, T: Tiebreaker<I> + Default> Landlord<I, T> {
-    /// Instantiate a new landlord replacement policy.
+impl<I: Item, T: Tiebreaker<I> + Default> LfuAging<I, T> {
+    /// Instantiate a new aging LFU replacement policy, which multiplies every count by
+    /// `age_factor` once per `age_interval` accesses. This defaults to halving the counts every 100
+    /// accesses.
     ///
-    /// The `credit_increase` parameter represents the percentage of the gap between the current credit
+    /// # Panics
+    ///
+    /// If `age_factor` is not between 0 and 1, or if `age_interval` is zero.
+    #[must_use]
+    pub fn new(age_factor: f64, age_interval: u32) -> Self {
+        Self::with_tiebreaker(T::default(), age_factor, age_interval)
+    }
+}
+
+impl<I: Item, T: Tiebreaker<I>> LfuAging<I, T> {
+    /// Instantiate a new aging LFU replacement policy, with a specifically configured tiebreaker.
+    ///
+    /// # Panics
+    ///
+    /// If `age_factor` is not between 0 and 1, or if `age_interval` is zero.
+    #[must_use]
+    pub fn with_tiebreaker(tiebreaker: T, age_factor: f64, age_interval: u32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&age_factor),
+            "The age factor must be between 0 and 1."
+        );
+        assert!(age_interval > 0, "The age interval must be positive.");
+
+        Self {
+            counts: HashMap::new(),
+            tiebreaker,
+            accesses: 0,
+            age_factor,
+            age_interval,
+        }
+    }
+}
+
+impl<I: Item, T: Tiebreaker<I>> ReplacementPolicy<I> for LfuAging<I, T> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        *self.counts.entry(next).or_insert(0) += 1;
+        self.tiebreaker.update_state(set, capacity, next);
+
+        self.accesses += 1;
+        if self.accesses == self.age_interval {
+            self.accesses = 0;
+            // the age factor is between 0 and 1, so the aged counts fit and are non-negative
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            for count in self.counts.values_mut() {
+                *count = (f64::from(*count) * self.age_factor) as u32;
+            }
+        }
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        self.update_state(set, capacity, next);
+        least_frequent_evict(
+            &self.counts,
+            &mut self.tiebreaker,
+            set,
+            capacity.saturating_sub(next.size()),
+        )
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        least_frequent_evict(&self.counts, &mut self.tiebreaker, set, capacity)
+    }
+
+    fn remove(&mut self, item: I) {
+        self.counts.remove(&item);
+        self.tiebreaker.remove(item);
+    }
+
+    fn bypass(&mut self, item: I) {
+        self.tiebreaker.bypass(item);
+    }
+}
+
+/// Evict the least frequently used items until the set fits in `capacity`.
+///
+/// This is shared by the LFU family of policies, which differ only in how they count.
+fn least_frequent_evict<I: Item, T: Tiebreaker<I>>(
+    counts: &HashMap<I, u32>,
+    tiebreaker: &mut T,
+    set: &HashSet<I>,
+    capacity: u32,
+) -> HashSet<I> {
+    let mut to_evict = HashSet::new();
+
+    loop {
+        let used = remaining_size(set, &to_evict);
+        if used <= capacity {
+            break;
+        }
+
+        let min = set
+            .iter()
+            .filter(|i| !to_evict.contains(*i))
+            .map(|i| counts[i])
+            .min()
+            .expect("The set is non-empty.");
+
+        to_evict.extend(
+            tiebreaker.tiebreak(
+                &set.iter()
+                    .filter(|i| !to_evict.contains(*i) && counts[*i] == min)
+                    .copied()
+                    .collect(),
+                used - capacity,
+            ),
+        );
+    }
+
+    to_evict
+}
+
+/// The landlord replacement algotihm.
+///
+/// Detailed in this paper: <https://arxiv.org/abs/cs/0205033>
+///
+/// The tiebreaker (for evicting multiple zero-credit items) defaults to Lru.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, Landlord, GeneralModelGenerator};
+///
+/// let mut cache = Cache::<Landlord, (), _>::new(3);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let a = g.item(1.0, 1);
+/// let b = g.item(0.5, 2);
+/// let c = g.item(100.0, 2);
+/// let d = g.item(1.0, 1);
+///
+/// cache.access(a);
+/// cache.access(b);
+/// cache.access(c);
+/// cache.access(d);
+///
+/// assert_eq!(cache.set(), &HashSet::from([c, d]));
+/// ```
+#[derive(Clone)]
+pub struct Landlord<I: Item = GeneralModelItem, T: Tiebreaker<I> = Lru<GeneralModelItem>> {
+    credit: HashMap<I, f64>,
+    credit_increase: f64,
+    tiebreaker: T,
+}
+
+impl<I: Item, T: Tiebreaker<I> + Default> Default for Landlord<I, T> {
+    fn default() -> Self {
+        Self {
+            credit: HashMap::default(),
+            credit_increase: 1.0,
+            tiebreaker: T::default(),
+        }
+    }
+}
+
+impl<I: Item, T: Tiebreaker<I> + Default> Landlord<I, T> {
+    /// Instantiate a new landlord replacement policy.
+    ///
+    /// The `credit_increase` parameter represents the percentage of the gap between the current credit
     /// and maximum credit (cost) to increase an item's credit when it is hit. It should not be above
     /// one. Higher values are closer to LRU, lower values are closer to FIFO. This defaults to 1,
     /// and should generally be between 0 and 1.
@@ -304,6 +901,8 @@ impl<I: Item, T: Tiebreaker<I>> ReplacementPolicy<I> for Landlord<I, T> {
                 self.credit.insert(next, next.cost());
             }
         } else {
+            // credit is compared per unit size, so it would be divided by zero
+            assert!(next.size() > 0, "Landlord can't cache zero-size items.");
             self.credit.insert(next, next.cost());
         }
 
@@ -311,20 +910,19 @@ impl<I: Item, T: Tiebreaker<I>> ReplacementPolicy<I> for Landlord<I, T> {
     }
 
     fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.update_state(set, capacity, next);
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
         let mut to_evict = HashSet::default();
 
-        while set
-            .iter()
-            .filter(|i| !to_evict.contains(*i))
-            .map(Item::size)
-            .sum::<u32>()
-            + next.size()
-            > capacity
-        {
+        while remaining_size(set, &to_evict) > capacity {
             // have to compute min cost by hand because of limitations with float
             let mut current_delta = f64::MAX;
             let mut current_min_item = None;
-            for item in set {
+            for item in set.iter().filter(|i| !to_evict.contains(*i)) {
                 let item_delta = *self
                     .credit
                     .get(item)
@@ -356,117 +954,2750 @@ impl<I: Item, T: Tiebreaker<I>> ReplacementPolicy<I> for Landlord<I, T> {
                         })
                         .copied()
                         .collect(),
-                    set.iter()
-                        .filter(|i| !to_evict.contains(*i))
-                        .map(Item::size)
-                        .sum::<u32>()
-                        + next.size()
-                        - capacity,
+                    remaining_size(set, &to_evict) - capacity,
                 ),
             );
         }
 
-        self.update_state(set, capacity, next);
-
         to_evict
     }
+
+    fn remove(&mut self, item: I) {
+        self.credit.remove(&item);
+        self.tiebreaker.remove(item);
+    }
+
+    fn bypass(&mut self, item: I) {
+        self.tiebreaker.bypass(item);
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::Cache;
+/// The evict-largest replacement policy, which evicts the largest items until there's room.
+///
+/// The tiebreaker (for evicting between items of the same size) defaults to Lru.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, EvictLargest, GeneralModelGenerator};
+///
+/// let mut cache = Cache::<EvictLargest, (), _>::new(4);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let a = g.item(1.0, 1);
+/// let b = g.item(1.0, 2);
+/// let c = g.item(1.0, 1);
+/// let d = g.item(1.0, 2);
+///
+/// cache.access(a);
+/// cache.access(b);
+/// cache.access(c);
+/// cache.access(d);
+///
+/// assert_eq!(cache.set(), &HashSet::from([a, c, d]));
+/// ```
+#[derive(Default, Clone)]
+pub struct EvictLargest<I: Item = GeneralModelItem, T: Tiebreaker<I> = Lru<GeneralModelItem>> {
+    tiebreaker: T,
+    _item: PhantomData<I>,
+}
 
-    macro_rules! integration_test {
-        ($name:ident ($policy:ty): $($in:expr),* => $($out:expr),*) => {
-            #[test]
-            fn $name() {
-                let mut c = Cache::<$policy>::new(3);
+impl<I: Item, T: Tiebreaker<I>> EvictLargest<I, T> {
+    /// Instantiate a new evict-largest replacement policy, with a specifically configured
+    /// tiebreaker.
+    #[must_use]
+    pub const fn with_tiebreaker(tiebreaker: T) -> Self {
+        Self {
+            tiebreaker,
+            _item: PhantomData,
+        }
+    }
+}
 
-                $(
-                    c.access($in);
-                )*
+impl<I: Item, T: Tiebreaker<I>> ReplacementPolicy<I> for EvictLargest<I, T> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        self.tiebreaker.update_state(set, capacity, next);
+    }
 
-                assert_eq!(c.set(), &HashSet::from([$($out),*]));
-            }
-        };
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.update_state(set, capacity, next);
+        to_evict
     }
 
-    macro_rules! replacement_policy_test {
-        ($name:ident ($policy:ty):
-            counting_up => $($counting_up_out:expr),*;
-            repeated => $($repeated_out:expr),*;
-            one_repetition => $($one_repetition_out:expr),*;
-            cycle => $($cycle_out:expr),*;
-        ) => {
-            mod $name {
-                use super::*;
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
 
-                integration_test!(counting_up ($policy): 0, 1, 2, 3 => $($counting_up_out),*);
-                integration_test!(repeated ($policy): 0, 0, 0, 0 =>  $($repeated_out),*);
-                integration_test!(one_repetition ($policy): 0, 1, 2, 0, 3 => $($one_repetition_out),*);
-                integration_test!(cycle ($policy): 0, 1, 2, 0, 1, 2, 0, 1, 2, 3, 3 => $($cycle_out),*);
+        loop {
+            let used = remaining_size(set, &to_evict);
+            if used <= capacity {
+                break;
             }
+
+            let max = set
+                .iter()
+                .filter(|i| !to_evict.contains(*i))
+                .map(Item::size)
+                .max()
+                .expect("The set is non-empty.");
+
+            to_evict.extend(
+                self.tiebreaker.tiebreak(
+                    &set.iter()
+                        .filter(|i| !to_evict.contains(*i) && i.size() == max)
+                        .copied()
+                        .collect(),
+                    used - capacity,
+                ),
+            );
         }
-    }
 
-    replacement_policy_test! {
-        lru (Lru):
-            counting_up => 1, 2, 3;
-            repeated => 0;
-            one_repetition => 0, 2, 3;
-            cycle => 1, 2, 3;
+        to_evict
     }
 
-    replacement_policy_test! {
-        mru (Mru):
-            counting_up => 0, 1, 3;
-            repeated => 0;
-            one_repetition => 1, 2, 3;
-            cycle => 0, 1, 3;
+    fn remove(&mut self, item: I) {
+        self.tiebreaker.remove(item);
     }
 
-    replacement_policy_test! {
-        fifo (Fifo):
-            counting_up => 1, 2, 3;
-            repeated => 0;
-            one_repetition => 1, 2, 3;
-            cycle => 1, 2, 3;
+    fn bypass(&mut self, item: I) {
+        self.tiebreaker.bypass(item);
     }
+}
 
-    mod landlord {
-        use super::*;
-        use crate::GeneralModelGenerator;
+/// The evict-cheapest replacement policy, which evicts the cheapest items until there's room.
+///
+/// The tiebreaker (for evicting between items of the same cost) defaults to Lru.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, EvictCheapest, GeneralModelGenerator};
+///
+/// let mut cache = Cache::<EvictCheapest, (), _>::new(3);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let expensive = g.item(100.0, 1);
+/// cache.access(expensive);
+///
+/// let cheap = (0..5).map(|_| g.item(1.0, 1)).collect::<Vec<_>>();
+/// for &item in &cheap {
+///     cache.access(item);
+/// }
+///
+/// assert_eq!(cache.set(), &HashSet::from([expensive, cheap[3], cheap[4]]));
+/// ```
+#[derive(Default, Clone)]
+pub struct EvictCheapest<I: Item = GeneralModelItem, T: Tiebreaker<I> = Lru<GeneralModelItem>> {
+    tiebreaker: T,
+    _item: PhantomData<I>,
+}
 
-        #[test]
-        fn lru_tiebreaker() {
-            let mut cache = Cache::<Landlord, (), _>::new(3);
-            let mut gen = GeneralModelGenerator::new();
+impl<I: Item, T: Tiebreaker<I>> EvictCheapest<I, T> {
+    /// Instantiate a new evict-cheapest replacement policy, with a specifically configured
+    /// tiebreaker.
+    #[must_use]
+    pub const fn with_tiebreaker(tiebreaker: T) -> Self {
+        Self {
+            tiebreaker,
+            _item: PhantomData,
+        }
+    }
+}
 
-            let a = gen.item(1.0, 1);
-            let b = gen.item(2.0, 2);
-            let c = gen.item(1.0, 1);
+impl<I: Item, T: Tiebreaker<I>> ReplacementPolicy<I> for EvictCheapest<I, T> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        self.tiebreaker.update_state(set, capacity, next);
+    }
 
-            cache.access(a);
-            cache.access(b);
-            cache.access(c);
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.update_state(set, capacity, next);
+        to_evict
+    }
 
-            // should evict a because LRU
-            assert_eq!(cache.set(), &HashSet::from([b, c]));
-        }
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
 
-        #[test]
-        fn credit_not_cost() {
-            // we had a bug where we would compute delta with the cost, not the credit. this tests
-            // that that is fixed.
-            let mut cache = Cache::<Landlord, (), _>::new(3);
-            let mut gen = GeneralModelGenerator::new();
+        loop {
+            let used = remaining_size(set, &to_evict);
+            if used <= capacity {
+                break;
+            }
 
-            let itm_a = gen.item(2.0, 1);
-            let itm_b = gen.item(3.0, 1);
-            let itm_c = gen.item(3.0, 1);
-            let itm_z = gen.item(2.0, 1);
-            let itm_d = gen.item(1.0, 1);
+            // have to compute min cost by hand because of limitations with float
+            let mut min = f64::MAX;
+            for item in set.iter().filter(|i| !to_evict.contains(*i)) {
+                if item.cost() < min {
+                    min = item.cost();
+                }
+            }
+
+            to_evict.extend(
+                self.tiebreaker.tiebreak(
+                    &set.iter()
+                        .filter(|i| !to_evict.contains(*i) && abs_diff_eq!(i.cost(), min))
+                        .copied()
+                        .collect(),
+                    used - capacity,
+                ),
+            );
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.tiebreaker.remove(item);
+    }
+
+    fn bypass(&mut self, item: I) {
+        self.tiebreaker.bypass(item);
+    }
+}
+
+/// The ARC (adaptive replacement cache) replacement policy.
+///
+/// Detailed in this paper: <https://www.usenix.org/legacy/events/fast03/tech/full_papers/megiddo/megiddo.pdf>
+///
+/// Resident items are split between `t1`, items seen once recently, and `t2`, items seen at least
+/// twice recently. The ghost lists `b1` and `b2` remember items recently evicted from `t1` and
+/// `t2` respectively; since these are no longer in the cache's set, the policy tracks them itself.
+/// Hits in the ghost lists adapt the target size `p` of `t1`. List sizes are measured with
+/// [`Item::size`], so for unit-size items this is exactly the algorithm from the paper.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Arc, Cache};
+///
+/// let mut c = Cache::<Arc>::new(3);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(0);
+/// c.access(3);
+///
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+#[derive(Default, Clone)]
+pub struct Arc<I: Item = u32> {
+    t1: VecDeque<I>,
+    t2: VecDeque<I>,
+    b1: VecDeque<I>,
+    b2: VecDeque<I>,
+    p: f64,
+}
+
+impl<I: Item> Arc<I> {
+    /// The total size of the items in a list.
+    fn size(list: &VecDeque<I>) -> u32 {
+        list.iter().map(Item::size).sum()
+    }
+
+    /// Remove an item from a list, returning whether it was present.
+    fn remove_from(list: &mut VecDeque<I>, item: I) -> bool {
+        list.iter()
+            .position(|&i| i == item)
+            .and_then(|index| list.remove(index))
+            .is_some()
+    }
+
+    /// The `REPLACE` subroutine from the paper: evict the LRU item of either `t1` or `t2` into the
+    /// corresponding ghost list.
+    fn evict(&mut self, in_b2: bool) -> I {
+        let t1 = f64::from(Self::size(&self.t1));
+
+        if !self.t1.is_empty()
+            && ((in_b2 && abs_diff_eq!(t1, self.p)) || t1 > self.p || self.t2.is_empty())
+        {
+            let item = self.t1.pop_front().expect("t1 is non-empty.");
+            self.b1.push_back(item);
+            item
+        } else {
+            let item = self.t2.pop_front().expect("The cache is non-empty.");
+            self.b2.push_back(item);
+            item
+        }
+    }
+
+    /// Handle an access to `next`, evicting items until it fits in the cache.
+    fn request(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+        let c = f64::from(capacity);
+        let needs_room = |to_evict: &HashSet<I>| {
+            set.iter()
+                .filter(|i| !to_evict.contains(*i))
+                .map(Item::size)
+                .sum::<u32>()
+                + next.size()
+                > capacity
+        };
+
+        if Self::remove_from(&mut self.t1, next) || Self::remove_from(&mut self.t2, next) {
+            // case I: a hit, so we just move the item to the top of t2
+            self.t2.push_back(next);
+            return to_evict;
+        }
+
+        let in_b2 = if Self::remove_from(&mut self.b1, next) {
+            // case II: a ghost hit in b1, so t1 should have been bigger
+            let (b1, b2) = (Self::size(&self.b1) + next.size(), Self::size(&self.b2));
+            let delta = if b1 >= b2 {
+                1.0
+            } else {
+                f64::from(b2) / f64::from(b1)
+            };
+            self.p = (self.p + delta).min(c);
+            false
+        } else if Self::remove_from(&mut self.b2, next) {
+            // case III: a ghost hit in b2, so t2 should have been bigger
+            let (b1, b2) = (Self::size(&self.b1), Self::size(&self.b2) + next.size());
+            let delta = if b2 >= b1 {
+                1.0
+            } else {
+                f64::from(b1) / f64::from(b2)
+            };
+            self.p = (self.p - delta).max(0.0);
+            true
+        } else {
+            // case IV: a completely new item, which goes in t1
+            let l1 = Self::size(&self.t1) + Self::size(&self.b1);
+            let total = l1 + Self::size(&self.t2) + Self::size(&self.b2);
+
+            if l1 >= capacity {
+                if Self::size(&self.t1) < capacity {
+                    self.b1.pop_front();
+                } else if needs_room(&to_evict) {
+                    // b1 is empty, so we evict from t1 without remembering the item
+                    to_evict.extend(self.t1.pop_front());
+                }
+            } else if total >= 2 * capacity {
+                self.b2.pop_front();
+            }
+
+            while needs_room(&to_evict) {
+                to_evict.insert(self.evict(false));
+            }
+
+            self.t1.push_back(next);
+            self.trim(capacity);
+            return to_evict;
+        };
+
+        while needs_room(&to_evict) {
+            to_evict.insert(self.evict(in_b2));
+        }
+
+        self.t2.push_back(next);
+        self.trim(capacity);
+        to_evict
+    }
+
+    /// Forget ghost items until the lists are within the bounds given by the paper.
+    ///
+    /// For unit-sized items this never does anything, but evicting several items at once in the
+    /// size-aware model can overfill the ghost lists.
+    fn trim(&mut self, capacity: u32) {
+        while Self::size(&self.t1) + Self::size(&self.b1) > capacity && !self.b1.is_empty() {
+            self.b1.pop_front();
+        }
+
+        while Self::size(&self.t1)
+            + Self::size(&self.t2)
+            + Self::size(&self.b1)
+            + Self::size(&self.b2)
+            > 2 * capacity
+            && !self.b2.is_empty()
+        {
+            self.b2.pop_front();
+        }
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for Arc<I> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        let evicted = self.request(set, capacity, next);
+        debug_assert!(evicted.is_empty());
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        self.request(set, capacity, next)
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            to_evict.insert(self.evict(false));
+        }
+
+        self.p = self.p.min(f64::from(capacity));
+        self.trim(capacity);
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        // the item wasn't evicted, so there's no reason to remember it in a ghost list
+        if !Self::remove_from(&mut self.t1, item) {
+            Self::remove_from(&mut self.t2, item);
+        }
+    }
+}
+
+/// The CLOCK replacement policy, an approximation of LRU also known as second chance.
+///
+/// Items sit in a circular buffer with a reference bit, which is set when they are hit. To evict,
+/// the clock hand sweeps the buffer, clearing set bits, until it finds an item whose bit is clear.
+/// New items are inserted just behind the hand, so they are the last to be considered.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, Clock};
+///
+/// let mut c = Cache::<Clock>::new(3);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(0);
+/// c.access(3);
+///
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+#[derive(Default, Clone)]
+pub struct Clock<I: Item = u32> {
+    buffer: Vec<(I, bool)>,
+    hand: usize,
+}
+
+impl<I: Item> Clock<I> {
+    /// Insert an item just behind the hand.
+    fn insert(&mut self, item: I) {
+        if self.hand >= self.buffer.len() {
+            self.hand = 0;
+        }
+
+        self.buffer.insert(self.hand, (item, false));
+        self.hand += 1;
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for Clock<I> {
+    fn update_state(&mut self, _: &HashSet<I>, _: u32, next: I) {
+        if let Some((_, referenced)) = self.buffer.iter_mut().find(|(i, _)| *i == next) {
+            *referenced = true;
+        } else {
+            self.insert(next);
+        }
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.insert(next);
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            if self.hand >= self.buffer.len() {
+                self.hand = 0;
+            }
+
+            let (item, referenced) = &mut self.buffer[self.hand];
+            if *referenced {
+                // give the item a second chance
+                *referenced = false;
+                self.hand += 1;
+            } else {
+                // removing the item moves the hand to the next one
+                to_evict.insert(*item);
+                self.buffer.remove(self.hand);
+            }
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        if let Some(index) = self.buffer.iter().position(|&(i, _)| i == item) {
+            self.buffer.remove(index);
+            if self.hand > index {
+                self.hand -= 1;
+            }
+        }
+    }
+
+    fn order(&self) -> Vec<I> {
+        // the hand sweeps forward from its current position
+        let hand = self.hand.min(self.buffer.len());
+        self.buffer[hand..]
+            .iter()
+            .chain(&self.buffer[..hand])
+            .map(|&(i, _)| i)
+            .collect()
+    }
+}
+
+/// The 2Q replacement policy.
+///
+/// Detailed in this paper: <https://www.vldb.org/conf/1994/P439.PDF>
+///
+/// New items enter `a1in`, a FIFO queue. Items evicted from `a1in` are remembered in `a1out`, a
+/// FIFO queue of ghost items which are no longer in the cache. Only items which are accessed again
+/// while in `a1out` make it into `am`, the LRU queue for hot items. This makes 2Q resistant to
+/// scans, which never leave `a1in`.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, TwoQ};
+///
+/// let mut c = Cache::<TwoQ>::new(4);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(3);
+/// c.access(4); // evicts 0 into a1out
+/// c.access(0); // promotes 0 into am, evicting 1
+///
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3, 4]));
+/// ```
+#[derive(Clone)]
+pub struct TwoQ<I: Item = u32> {
+    a1in: VecDeque<I>,
+    a1out: VecDeque<I>,
+    am: Vec<I>,
+    kin: f64,
+    kout: f64,
+}
+
+impl<I: Item> Default for TwoQ<I> {
+    fn default() -> Self {
+        // these are the values recommended by the paper
+        Self::new(0.25, 0.5)
+    }
+}
+
+impl<I: Item> TwoQ<I> {
+    /// Instantiate a new 2Q replacement policy.
+    ///
+    /// The `kin` and `kout` parameters are the maximum sizes of `a1in` and `a1out`, as fractions of
+    /// the cache's capacity. They default to 0.25 and 0.5 respectively, as recommended by the
+    /// paper.
+    #[must_use]
+    pub const fn new(kin: f64, kout: f64) -> Self {
+        Self {
+            a1in: VecDeque::new(),
+            a1out: VecDeque::new(),
+            am: Vec::new(),
+            kin,
+            kout,
+        }
+    }
+
+    /// Evict a single item to make room, following the paper's `reclaimfor` procedure.
+    fn reclaim(&mut self, capacity: u32) -> I {
+        let a1in = f64::from(self.a1in.iter().map(Item::size).sum::<u32>());
+
+        if a1in > self.kin * f64::from(capacity) || self.am.is_empty() {
+            let item = self.a1in.pop_front().expect("The cache is non-empty.");
+            self.a1out.push_back(item);
+
+            #[allow(clippy::while_float)] // the left hand side is integral, so this is fine
+            while f64::from(self.a1out.iter().map(Item::size).sum::<u32>())
+                > self.kout * f64::from(capacity)
+            {
+                self.a1out.pop_front();
+            }
+
+            item
+        } else {
+            // evicted hot items aren't remembered in a1out
+            self.am.remove(0)
+        }
+    }
+
+    /// Record an access to `next`, which must already have room in the cache.
+    fn insert(&mut self, next: I) {
+        if let Some(index) = self.am.iter().position(|&i| i == next) {
+            self.am.remove(index);
+            self.am.push(next);
+        } else if let Some(index) = self.a1out.iter().position(|&i| i == next) {
+            self.a1out.remove(index);
+            self.am.push(next);
+        } else if !self.a1in.contains(&next) {
+            self.a1in.push_back(next);
+        }
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for TwoQ<I> {
+    fn update_state(&mut self, _: &HashSet<I>, _: u32, next: I) {
+        self.insert(next);
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while set
+            .iter()
+            .filter(|i| !to_evict.contains(*i))
+            .map(Item::size)
+            .sum::<u32>()
+            + next.size()
+            > capacity
+        {
+            to_evict.insert(self.reclaim(capacity));
+        }
+
+        self.insert(next);
+
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            to_evict.insert(self.reclaim(capacity));
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.a1in.retain(|&i| i != item);
+        self.am.retain(|&i| i != item);
+    }
+}
+
+/// The segmented LRU replacement policy.
+///
+/// The cache is split into a probationary segment and a protected segment, each of which is an
+/// LRU stack. Items enter the probationary segment, and are promoted to the protected segment when
+/// they are hit. When the protected segment overflows, its least recently used item is demoted back
+/// to the top of the probationary segment. Items are only ever evicted from the bottom of the
+/// probationary segment.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, Slru};
+///
+/// let mut c = Cache::<Slru>::new(3);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(0);
+/// c.access(3);
+///
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+///
+/// Unlike LRU, an item which has been hit survives a run of new items:
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, Slru};
+///
+/// let mut c = Cache::<Slru>::new(3);
+///
+/// c.access(0);
+/// c.access(0); // 0 is now protected
+/// c.access(1);
+/// c.access(2);
+/// c.access(3);
+/// c.access(4);
+///
+/// assert_eq!(c.set(), &HashSet::from([0, 3, 4]));
+/// ```
+#[derive(Clone)]
+pub struct Slru<I: Item = u32> {
+    probationary: Vec<I>,
+    protected: Vec<I>,
+    protected_fraction: f64,
+}
+
+impl<I: Item> Default for Slru<I> {
+    fn default() -> Self {
+        Self::new(0.8)
+    }
+}
+
+impl<I: Item> Slru<I> {
+    /// Instantiate a new segmented LRU replacement policy.
+    ///
+    /// The `protected_fraction` parameter is the maximum size of the protected segment, as a
+    /// fraction of the cache's capacity. It should be between 0 and 1, and defaults to 0.8.
+    #[must_use]
+    pub const fn new(protected_fraction: f64) -> Self {
+        Self {
+            probationary: Vec::new(),
+            protected: Vec::new(),
+            protected_fraction,
+        }
+    }
+
+    /// Check whether the protected segment is over its maximum size.
+    fn protected_overflows(&self, capacity: u32) -> bool {
+        f64::from(self.protected.iter().map(Item::size).sum::<u32>())
+            > self.protected_fraction * f64::from(capacity)
+    }
+
+    /// Demote items from the bottom of the protected segment to the top of the probationary
+    /// segment, until the protected segment is within its maximum size.
+    fn demote(&mut self, capacity: u32) {
+        while self.protected_overflows(capacity) {
+            self.probationary.push(self.protected.remove(0));
+        }
+    }
+
+    /// Check whether an item is in either segment.
+    fn contains(&self, item: I) -> bool {
+        self.probationary.contains(&item) || self.protected.contains(&item)
+    }
+
+    /// The item that would be evicted next.
+    ///
+    /// This is the bottom of the probationary segment, unless everything is protected.
+    fn victim(&self) -> Option<I> {
+        self.probationary
+            .first()
+            .or_else(|| self.protected.first())
+            .copied()
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for Slru<I> {
+    fn update_state(&mut self, _: &HashSet<I>, capacity: u32, next: I) {
+        if let Some(index) = self.protected.iter().position(|&i| i == next) {
+            self.protected.remove(index);
+            self.protected.push(next);
+        } else if let Some(index) = self.probationary.iter().position(|&i| i == next) {
+            self.probationary.remove(index);
+            self.protected.push(next);
+            self.demote(capacity);
+        } else {
+            self.probationary.push(next);
+        }
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while set
+            .iter()
+            .filter(|i| !to_evict.contains(*i))
+            .map(Item::size)
+            .sum::<u32>()
+            + next.size()
+            > capacity
+        {
+            let victim = self.victim().expect("The cache is non-empty.");
+            self.remove(victim);
+            to_evict.insert(victim);
+        }
+
+        self.update_state(set, capacity, next);
+
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            let victim = self.victim().expect("The cache is non-empty.");
+            self.remove(victim);
+            to_evict.insert(victim);
+        }
+
+        // the protected segment's maximum size shrinks with the cache
+        self.demote(capacity);
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.probationary.retain(|&i| i != item);
+        self.protected.retain(|&i| i != item);
+    }
+
+    fn order(&self) -> Vec<I> {
+        self.probationary
+            .iter()
+            .chain(&self.protected)
+            .copied()
+            .collect()
+    }
+}
+
+/// The status of an item tracked by [`Lirs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LirsStatus {
+    Lir,
+    ResidentHir,
+    NonResidentHir,
+}
+
+/// The LIRS (low inter-reference recency set) replacement policy.
+///
+/// Detailed in this paper: <https://dl.acm.org/doi/10.1145/511399.511340>
+///
+/// Items with low inter-reference recency are LIR items, and are always resident. Other items are
+/// HIR items; only a small portion of the cache holds resident HIR items, in the queue `q`, and the
+/// front of that queue is what gets evicted. The recency stack `s` also remembers evicted
+/// (non-resident) HIR items, which are no longer in the cache's set, so that an item re-accessed
+/// soon after its eviction can become LIR. This makes LIRS resistant to loops larger than the
+/// cache, where LRU thrashes.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, Lirs};
+///
+/// let mut c = Cache::<Lirs>::new(3);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(0);
+/// c.access(3);
+///
+/// // 0 and 1 are LIR items, so we evict the resident HIR item 2
+/// assert_eq!(c.set(), &HashSet::from([0, 1, 3]));
+/// ```
+#[derive(Clone)]
+pub struct Lirs<I: Item = u32> {
+    s: Vec<I>,
+    q: VecDeque<I>,
+    status: HashMap<I, LirsStatus>,
+    hir_fraction: f64,
+}
+
+impl<I: Item> Default for Lirs<I> {
+    fn default() -> Self {
+        Self::new(0.01)
+    }
+}
+
+impl<I: Item> Lirs<I> {
+    /// Instantiate a new LIRS replacement policy.
+    ///
+    /// The `hir_fraction` parameter is the portion of the cache's capacity reserved for resident
+    /// HIR items. It defaults to 0.01, as recommended by the paper. Because LIR items must fit in
+    /// the rest of the cache, small caches will always reserve at least one item's worth of space.
+    #[must_use]
+    pub fn new(hir_fraction: f64) -> Self {
+        Self {
+            s: Vec::new(),
+            q: VecDeque::new(),
+            status: HashMap::new(),
+            hir_fraction,
+        }
+    }
+
+    /// Check whether the LIR items, plus `extra`, fit in the space allocated for them.
+    fn lir_fits(&self, capacity: u32, extra: u32) -> bool {
+        let lir = self
+            .status
+            .iter()
+            .filter(|&(_, &status)| status == LirsStatus::Lir)
+            .map(|(i, _)| i.size())
+            .sum::<u32>();
+
+        f64::from(lir + extra) <= (1.0 - self.hir_fraction) * f64::from(capacity)
+    }
+
+    /// Move an item to the top of the stack, inserting it if it isn't already there.
+    fn move_to_top(&mut self, item: I) {
+        if let Some(index) = self.s.iter().position(|&i| i == item) {
+            self.s.remove(index);
+        }
+        self.s.push(item);
+    }
+
+    /// Remove HIR items from the bottom of the stack until the bottom item is LIR.
+    fn prune(&mut self) {
+        while let Some(&bottom) = self.s.first() {
+            match self.status[&bottom] {
+                LirsStatus::Lir => break,
+                LirsStatus::ResidentHir => {
+                    // it's still in q, so we have to keep tracking it
+                    self.s.remove(0);
+                }
+                LirsStatus::NonResidentHir => {
+                    self.s.remove(0);
+                    self.status.remove(&bottom);
+                }
+            }
+        }
+    }
+
+    /// Make `item` an LIR item, demoting LIR items from the bottom of the stack until the LIR items
+    /// fit in their allocated space.
+    fn promote(&mut self, capacity: u32, item: I) {
+        self.q.retain(|&i| i != item);
+        self.status.insert(item, LirsStatus::Lir);
+
+        // if there weren't any lir items, hir items can be at the bottom of the stack, and we must
+        // not demote those
+        self.prune();
+
+        while !self.lir_fits(capacity, 0) {
+            let bottom = self.s[0];
+            if bottom == item {
+                break;
+            }
+
+            self.s.remove(0);
+            self.status.insert(bottom, LirsStatus::ResidentHir);
+            self.q.push_back(bottom);
+            self.prune();
+        }
+    }
+
+    /// Evict a single item, which is the front of `q` unless there are no resident HIR items.
+    fn evict(&mut self) -> I {
+        if let Some(item) = self.q.pop_front() {
+            if self.s.contains(&item) {
+                self.status.insert(item, LirsStatus::NonResidentHir);
+            } else {
+                self.status.remove(&item);
+            }
+            item
+        } else {
+            // everything resident is LIR, and the bottom of the stack is always LIR
+            let item = self.s.remove(0);
+            self.status.remove(&item);
+            self.prune();
+            item
+        }
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for Lirs<I> {
+    fn update_state(&mut self, _: &HashSet<I>, capacity: u32, next: I) {
+        match self.status.get(&next).copied() {
+            Some(LirsStatus::Lir) => {
+                let was_bottom = self.s.first() == Some(&next);
+                self.move_to_top(next);
+                if was_bottom {
+                    self.prune();
+                }
+            }
+            Some(LirsStatus::ResidentHir) => {
+                if self.s.contains(&next) {
+                    // the item's inter-reference recency is lower than the bottom lir item
+                    self.move_to_top(next);
+                    self.promote(capacity, next);
+                } else {
+                    self.s.push(next);
+                    self.q.retain(|&i| i != next);
+                    self.q.push_back(next);
+                }
+            }
+            Some(LirsStatus::NonResidentHir) => {
+                self.move_to_top(next);
+                self.promote(capacity, next);
+            }
+            None => {
+                self.s.push(next);
+                if self.lir_fits(capacity, next.size()) {
+                    // while the cache is warming up, new items are lir
+                    self.status.insert(next, LirsStatus::Lir);
+                } else {
+                    self.status.insert(next, LirsStatus::ResidentHir);
+                    self.q.push_back(next);
+                }
+            }
+        }
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while set
+            .iter()
+            .filter(|i| !to_evict.contains(*i))
+            .map(Item::size)
+            .sum::<u32>()
+            + next.size()
+            > capacity
+        {
+            to_evict.insert(self.evict());
+        }
+
+        self.update_state(set, capacity, next);
+
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            to_evict.insert(self.evict());
+        }
+
+        // the space allocated to lir items shrinks with the cache, so demote the bottom of the
+        // stack
+        self.prune();
+        while !self.lir_fits(capacity, 0) {
+            let bottom = self.s.remove(0);
+            self.status.insert(bottom, LirsStatus::ResidentHir);
+            self.q.push_back(bottom);
+            self.prune();
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.q.retain(|&i| i != item);
+        self.s.retain(|&i| i != item);
+        self.status.remove(&item);
+        self.prune();
+    }
+}
+
+/// Belady's offline optimal replacement policy (MIN), which evicts the item whose next use is
+/// farthest in the future.
+///
+/// Because this needs to look ahead, it is constructed from the trace that the cache will be run
+/// on, and must be used on exactly that trace. Items which are never used again are treated as
+/// infinitely far away; ties between them are broken by evicting the least recently used.
+///
+/// ```
+/// use cache_sim::{Belady, Cache, Trace};
+/// use cache_sim::stats::MissCount;
+///
+/// let trace = Trace::from(vec![7, 0, 1, 2, 0, 3, 0, 4, 2, 3, 0, 3, 2, 1, 2, 0, 1, 7, 0, 1]);
+/// let mut c = Cache::<Belady, MissCount>::with_replacement_policy(Belady::new(&trace), 3_u32);
+///
+/// c.run_trace(&trace);
+///
+/// assert_eq!(c.stat().0, 9);
+/// ```
+#[derive(Clone)]
+pub struct Belady<I: Item = u32> {
+    trace: Vec<I>,
+    next_use: Vec<Option<usize>>,
+    position: usize,
+    // for each item seen so far, the position of its next use and of its last use
+    uses: HashMap<I, (Option<usize>, usize)>,
+}
+
+impl<I: Item> Belady<I> {
+    /// Instantiate a new Belady replacement policy for the given trace.
+    #[must_use]
+    pub fn new(trace: &Trace<I>) -> Self {
+        let mut next_use = vec![None; trace.len()];
+        let mut seen = HashMap::new();
+
+        // walk backwards, so that when we reach an access we've already seen its next use
+        for (i, item) in trace.iter().enumerate().rev() {
+            next_use[i] = seen.insert(*item, i);
+        }
+
+        Self {
+            trace: trace.inner().to_vec(),
+            next_use,
+            position: 0,
+            uses: HashMap::new(),
+        }
+    }
+
+    /// The items whose next uses are farthest away, such that the rest of the set fits in
+    /// `capacity`.
+    fn victims(&self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            let victim = *set
+                .iter()
+                .filter(|i| !to_evict.contains(*i))
+                .max_by_key(|i| {
+                    let (next_use, last_use) = self.uses[i];
+                    (next_use.unwrap_or(usize::MAX), std::cmp::Reverse(last_use))
+                })
+                .expect("The set is non-empty.");
+            to_evict.insert(victim);
+        }
+
+        to_evict
+    }
+
+    /// Record an access to `next`, advancing through the trace.
+    fn advance(&mut self, next: I) {
+        assert_eq!(
+            self.trace.get(self.position),
+            Some(&next),
+            "Belady must be run on the trace it was constructed from."
+        );
+
+        self.uses
+            .insert(next, (self.next_use[self.position], self.position));
+        self.position += 1;
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for Belady<I> {
+    fn update_state(&mut self, _: &HashSet<I>, _: u32, next: I) {
+        self.advance(next);
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.victims(set, capacity.saturating_sub(next.size()));
+        self.advance(next);
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        self.victims(set, capacity)
+    }
+
+    fn bypass(&mut self, item: I) {
+        // the item isn't inserted, but it's still a step through the trace
+        self.advance(item);
+    }
+
+    fn would_replace(&self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        self.victims(set, capacity.saturating_sub(next.size()))
+    }
+}
+
+/// A count-min sketch, which estimates the frequency of items in a small, fixed amount of space.
+///
+/// Every `sample_size` increments, all counters are halved, so that old frequencies age out.
+#[derive(Clone)]
+struct CountMinSketch {
+    counters: Vec<Vec<u32>>,
+    increments: usize,
+    sample_size: usize,
+}
+
+impl CountMinSketch {
+    fn new(width: usize, depth: usize, sample_size: usize) -> Self {
+        Self {
+            counters: vec![vec![0; width]; depth],
+            increments: 0,
+            sample_size,
+        }
+    }
+
+    /// The counter in `row` that `item` hashes to.
+    fn index<I: Item>(&self, row: usize, item: &I) -> usize {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (row, item).hash(&mut hasher);
+
+        #[allow(clippy::cast_possible_truncation)] // we only need some bits of the hash
+        let hash = hasher.finish() as usize;
+        hash % self.counters[row].len()
+    }
+
+    fn increment<I: Item>(&mut self, item: &I) {
+        for row in 0..self.counters.len() {
+            let index = self.index(row, item);
+            self.counters[row][index] += 1;
+        }
+
+        self.increments += 1;
+        if self.increments >= self.sample_size {
+            for counter in self.counters.iter_mut().flatten() {
+                *counter /= 2;
+            }
+            self.increments = 0;
+        }
+    }
+
+    fn estimate<I: Item>(&self, item: &I) -> u32 {
+        (0..self.counters.len())
+            .map(|row| self.counters[row][self.index(row, item)])
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// The W-TinyLFU replacement policy, as used by the Caffeine caching library.
+///
+/// Detailed in this paper: <https://arxiv.org/abs/1512.00727>
+///
+/// New items enter a small LRU window. Items evicted from the window are only admitted to the main
+/// region, a [`Slru`], if their estimated frequency is higher than that of the item the main region
+/// would evict. Frequencies are estimated by a count-min sketch, which is periodically aged so that
+/// formerly hot items are eventually forgotten.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, WTinyLfu};
+///
+/// let mut c = Cache::<WTinyLfu>::new(3);
+///
+/// c.access(0);
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(3);
+///
+/// // 2 was evicted from the window, but it's less frequent than anything in the main region
+/// assert_eq!(c.set(), &HashSet::from([0, 1, 3]));
+/// ```
+#[derive(Clone)]
+pub struct WTinyLfu<I: Item = u32> {
+    window: Vec<I>,
+    main: Slru<I>,
+    sketch: CountMinSketch,
+    window_fraction: f64,
+}
+
+impl<I: Item> Default for WTinyLfu<I> {
+    fn default() -> Self {
+        Self::new(256, 4, 0.01)
+    }
+}
+
+impl<I: Item> WTinyLfu<I> {
+    /// Instantiate a new W-TinyLFU replacement policy.
+    ///
+    /// The sketch has `depth` rows of `width` counters each, and is aged after every `10 * width`
+    /// accesses. The `window_fraction` parameter is the size of the window as a fraction of the
+    /// cache's capacity; the window always holds at least the most recently accessed item. These
+    /// default to 256, 4, and 0.01 respectively.
+    ///
+    /// # Panics
+    ///
+    /// If `width` or `depth` is zero.
+    #[must_use]
+    pub fn new(width: usize, depth: usize, window_fraction: f64) -> Self {
+        assert!(width > 0 && depth > 0, "The sketch must be non-empty.");
+
+        Self {
+            window: Vec::new(),
+            main: Slru::default(),
+            sketch: CountMinSketch::new(width, depth, 10 * width),
+            window_fraction,
+        }
+    }
+
+    /// The capacity allocated to the window.
+    fn window_capacity(&self, capacity: u32) -> u32 {
+        // the fraction is between zero and one, so this fits in a u32
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let window = (self.window_fraction * f64::from(capacity)).floor() as u32;
+        window
+    }
+
+    /// Handle an access to `next`, evicting items until it fits in the cache.
+    fn request(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+        let window_capacity = self.window_capacity(capacity);
+        let main_capacity = capacity - window_capacity;
+
+        self.sketch.increment(&next);
+
+        if let Some(index) = self.window.iter().position(|&i| i == next) {
+            self.window.remove(index);
+            self.window.push(next);
+            return to_evict;
+        }
+
+        if self.main.contains(next) {
+            self.main.update_state(set, main_capacity, next);
+            return to_evict;
+        }
+
+        let overfull = |to_evict: &HashSet<I>| {
+            set.iter()
+                .filter(|i| !to_evict.contains(*i))
+                .map(Item::size)
+                .sum::<u32>()
+                + next.size()
+                > capacity
+        };
+
+        self.window.push(next);
+
+        while self.window.iter().map(Item::size).sum::<u32>() > window_capacity
+            && self.window.len() > 1
+        {
+            let candidate = self.window.remove(0);
+            let mut admitted = true;
+
+            // the candidate and the main region's victim compete for the space
+            while overfull(&to_evict) {
+                match self.main.victim() {
+                    Some(victim)
+                        if self.sketch.estimate(&candidate) > self.sketch.estimate(&victim) =>
+                    {
+                        self.main.remove(victim);
+                        to_evict.insert(victim);
+                    }
+                    _ => {
+                        to_evict.insert(candidate);
+                        admitted = false;
+                        break;
+                    }
+                }
+            }
+
+            if admitted {
+                self.main.update_state(set, main_capacity, candidate);
+            }
+        }
+
+        // the window is small enough, but we might still need room for a large item
+        while overfull(&to_evict) {
+            let victim = self.main.victim().unwrap_or_else(|| self.window[0]);
+            self.main.remove(victim);
+            self.window.retain(|&i| i != victim);
+            to_evict.insert(victim);
+        }
+
+        to_evict
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for WTinyLfu<I> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        let evicted = self.request(set, capacity, next);
+        debug_assert!(evicted.is_empty());
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        self.request(set, capacity, next)
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            let victim = self.main.victim().unwrap_or_else(|| self.window[0]);
+            self.main.remove(victim);
+            self.window.retain(|&i| i != victim);
+            to_evict.insert(victim);
+        }
+
+        // the window will shrink on the next access, but the main region has to be fixed up now
+        self.main.demote(capacity - self.window_capacity(capacity));
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.window.retain(|&i| i != item);
+        self.main.remove(item);
+    }
+}
+
+/// The S3-FIFO replacement policy.
+///
+/// Detailed in this paper: <https://dl.acm.org/doi/10.1145/3600006.3613147>
+///
+/// New items enter a small FIFO queue. When they reach the end of it, items which were hit in the
+/// meantime move to the main FIFO queue, and the rest are evicted and remembered in a ghost queue;
+/// a later access to a ghost item inserts it directly into the main queue. Each item has a small
+/// frequency counter, which also gives items at the end of the main queue another chance.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, S3Fifo};
+///
+/// let mut c = Cache::<S3Fifo>::new(3);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(0); // 0 will move to the main queue instead of being evicted
+/// c.access(3);
+///
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+#[derive(Clone)]
+pub struct S3Fifo<I: Item = u32> {
+    small: VecDeque<I>,
+    main: VecDeque<I>,
+    ghost: VecDeque<I>,
+    freq: HashMap<I, u8>,
+    small_fraction: f64,
+}
+
+impl<I: Item> Default for S3Fifo<I> {
+    fn default() -> Self {
+        Self::new(0.1)
+    }
+}
+
+impl<I: Item> S3Fifo<I> {
+    /// The maximum value of an item's frequency counter.
+    const MAX_FREQ: u8 = 3;
+
+    /// Instantiate a new S3-FIFO replacement policy.
+    ///
+    /// The `small_fraction` parameter is the size of the small queue as a fraction of the cache's
+    /// capacity; the main and ghost queues get the rest. It defaults to 0.1, as recommended by the
+    /// paper.
+    #[must_use]
+    pub fn new(small_fraction: f64) -> Self {
+        Self {
+            small: VecDeque::new(),
+            main: VecDeque::new(),
+            ghost: VecDeque::new(),
+            freq: HashMap::new(),
+            small_fraction,
+        }
+    }
+
+    /// Check whether the total size of a queue is over `limit`.
+    fn overflows(queue: &VecDeque<I>, limit: f64) -> bool {
+        f64::from(queue.iter().map(Item::size).sum::<u32>()) > limit
+    }
+
+    /// Evict from the small queue, moving frequently used items to the main queue.
+    fn evict_small(&mut self, capacity: u32, to_evict: &mut HashSet<I>) {
+        let main_capacity = (1.0 - self.small_fraction) * f64::from(capacity);
+
+        while let Some(item) = self.small.pop_front() {
+            if self.freq[&item] > 0 {
+                self.main.push_back(item);
+                if Self::overflows(&self.main, main_capacity) {
+                    self.evict_main(to_evict);
+                }
+            } else {
+                self.freq.remove(&item);
+                to_evict.insert(item);
+
+                self.ghost.push_back(item);
+                while Self::overflows(&self.ghost, main_capacity) {
+                    self.ghost.pop_front();
+                }
+
+                return;
+            }
+        }
+    }
+
+    /// Evict from the main queue, reinserting items with a non-zero frequency.
+    fn evict_main(&mut self, to_evict: &mut HashSet<I>) {
+        while let Some(item) = self.main.pop_front() {
+            let freq = self.freq.get_mut(&item).expect("The item is in the cache.");
+            if *freq > 0 {
+                *freq -= 1;
+                self.main.push_back(item);
+            } else {
+                self.freq.remove(&item);
+                to_evict.insert(item);
+                return;
+            }
+        }
+    }
+
+    /// Remove an item from the ghost queue, returning whether it was there.
+    fn remove_ghost(&mut self, item: I) -> bool {
+        self.ghost
+            .iter()
+            .position(|&i| i == item)
+            .and_then(|index| self.ghost.remove(index))
+            .is_some()
+    }
+
+    /// Record an access to `next`, which must already have room in the cache.
+    fn insert(&mut self, next: I, was_ghost: bool) {
+        if let Some(freq) = self.freq.get_mut(&next) {
+            *freq = (*freq + 1).min(Self::MAX_FREQ);
+        } else {
+            if was_ghost {
+                self.main.push_back(next);
+            } else {
+                self.small.push_back(next);
+            }
+            self.freq.insert(next, 0);
+        }
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for S3Fifo<I> {
+    fn update_state(&mut self, _: &HashSet<I>, _: u32, next: I) {
+        let was_ghost = self.remove_ghost(next);
+        self.insert(next, was_ghost);
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        // check this first, because evicting can push `next` out of the ghost queue
+        let was_ghost = self.remove_ghost(next);
+
+        while set
+            .iter()
+            .filter(|i| !to_evict.contains(*i))
+            .map(Item::size)
+            .sum::<u32>()
+            + next.size()
+            > capacity
+        {
+            let small = f64::from(self.small.iter().map(Item::size).sum::<u32>());
+            if !self.small.is_empty()
+                && (small >= self.small_fraction * f64::from(capacity) || self.main.is_empty())
+            {
+                self.evict_small(capacity, &mut to_evict);
+            } else {
+                self.evict_main(&mut to_evict);
+            }
+        }
+
+        self.insert(next, was_ghost);
+
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            let small = f64::from(self.small.iter().map(Item::size).sum::<u32>());
+            if !self.small.is_empty()
+                && (small >= self.small_fraction * f64::from(capacity) || self.main.is_empty())
+            {
+                self.evict_small(capacity, &mut to_evict);
+            } else {
+                self.evict_main(&mut to_evict);
+            }
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.small.retain(|&i| i != item);
+        self.main.retain(|&i| i != item);
+        self.freq.remove(&item);
+    }
+}
+
+/// The GreedyDual-Size replacement policy.
+///
+/// Detailed in this paper: <https://www.usenix.org/legacy/publications/library/proceedings/usits97/full_papers/cao/cao.pdf>
+///
+/// Each item is given a value of `L + cost / size` when it is accessed, where `L` is an inflation
+/// value. The item with the lowest value is evicted, and `L` is raised to that value, so that
+/// items which haven't been accessed in a while are eventually evicted.
+///
+/// The tiebreaker (for evicting multiple items with the same value) defaults to Lru.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, GeneralModelGenerator, GreedyDualSize};
+///
+/// let mut cache = Cache::<GreedyDualSize, (), _>::new(3);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let a = g.item(1.0, 1);
+/// let b = g.item(0.5, 2);
+/// let c = g.item(100.0, 2);
+/// let d = g.item(1.0, 1);
+///
+/// cache.access(a);
+/// cache.access(b);
+/// cache.access(c);
+/// cache.access(d);
+///
+/// assert_eq!(cache.set(), &HashSet::from([c, d]));
+/// ```
+#[derive(Clone)]
+pub struct GreedyDualSize<I: Item = GeneralModelItem, T: Tiebreaker<I> = Lru<GeneralModelItem>> {
+    value: HashMap<I, f64>,
+    inflation: f64,
+    tiebreaker: T,
+}
+
+impl<I: Item, T: Tiebreaker<I> + Default> Default for GreedyDualSize<I, T> {
+    fn default() -> Self {
+        Self::with_tiebreaker(T::default())
+    }
+}
+
+impl<I: Item, T: Tiebreaker<I>> GreedyDualSize<I, T> {
+    /// Instantiate a new GreedyDual-Size replacement policy, with a specifically configured
+    /// tiebreaker.
+    #[must_use]
+    pub fn with_tiebreaker(tiebreaker: T) -> Self {
+        Self {
+            value: HashMap::default(),
+            inflation: 0.0,
+            tiebreaker,
+        }
+    }
+}
+
+impl<I: Item, T: Tiebreaker<I>> ReplacementPolicy<I> for GreedyDualSize<I, T> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        // values are compared per unit size, so they would be divided by zero
+        assert!(
+            next.size() > 0,
+            "GreedyDualSize can't cache zero-size items."
+        );
+        self.value
+            .insert(next, self.inflation + next.cost() / f64::from(next.size()));
+        self.tiebreaker.update_state(set, capacity, next);
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.update_state(set, capacity, next);
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let to_evict = greedy_dual_evict(
+            &self.value,
+            &mut self.inflation,
+            &mut self.tiebreaker,
+            set,
+            capacity,
+        );
+
+        for item in &to_evict {
+            self.value.remove(item);
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.value.remove(&item);
+        self.tiebreaker.remove(item);
+    }
+
+    fn bypass(&mut self, item: I) {
+        self.tiebreaker.bypass(item);
+    }
+}
+
+/// Evict the items with the lowest values until the set fits in `capacity`, raising the inflation
+/// value to the value of the evicted items.
+///
+/// This is shared by the greedy dual family of policies, which differ only in how they value items.
+fn greedy_dual_evict<I: Item, T: Tiebreaker<I>>(
+    value: &HashMap<I, f64>,
+    inflation: &mut f64,
+    tiebreaker: &mut T,
+    set: &HashSet<I>,
+    capacity: u32,
+) -> HashSet<I> {
+    let mut to_evict = HashSet::default();
+
+    while remaining_size(set, &to_evict) > capacity {
+        // have to compute min value by hand because of limitations with float
+        let mut min = f64::MAX;
+        for item in set.iter().filter(|i| !to_evict.contains(*i)) {
+            if value[item] < min {
+                min = value[item];
+            }
+        }
+
+        *inflation = min;
+
+        to_evict.extend(
+            tiebreaker.tiebreak(
+                &set.iter()
+                    .filter(|&i| !to_evict.contains(i))
+                    .filter(|&i| abs_diff_eq!(value[i], min))
+                    .copied()
+                    .collect(),
+                remaining_size(set, &to_evict) - capacity,
+            ),
+        );
+    }
+
+    to_evict
+}
+
+/// The GreedyDual-Size-Frequency replacement policy.
+///
+/// Detailed in this paper: <https://www.hpl.hp.com/techreports/98/HPL-98-69R1.pdf>
+///
+/// This is [`GreedyDualSize`], but each item's value is `L + frequency * cost / size`, where
+/// `frequency` is the number of times the item has been accessed since it entered the cache. This
+/// lets small items which are hit often stay in the cache, even if they are cheap.
+///
+/// The tiebreaker (for evicting multiple items with the same value) defaults to Lru.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, GeneralModelGenerator, GreedyDualSizeFrequency};
+///
+/// let mut cache = Cache::<GreedyDualSizeFrequency, (), _>::new(3);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let a = g.item(1.0, 1);
+/// let b = g.item(1.5, 1);
+/// let c = g.item(1.0, 1);
+/// let d = g.item(1.0, 1);
+///
+/// cache.access(a);
+/// cache.access(a);
+/// cache.access(b);
+/// cache.access(c);
+/// cache.access(d);
+///
+/// // a was accessed twice, so it's worth more than b
+/// assert_eq!(cache.set(), &HashSet::from([a, b, d]));
+/// ```
+#[derive(Clone)]
+pub struct GreedyDualSizeFrequency<
+    I: Item = GeneralModelItem,
+    T: Tiebreaker<I> = Lru<GeneralModelItem>,
+> {
+    value: HashMap<I, f64>,
+    counts: HashMap<I, u32>,
+    inflation: f64,
+    tiebreaker: T,
+}
+
+impl<I: Item, T: Tiebreaker<I> + Default> Default for GreedyDualSizeFrequency<I, T> {
+    fn default() -> Self {
+        Self::with_tiebreaker(T::default())
+    }
+}
+
+impl<I: Item, T: Tiebreaker<I>> GreedyDualSizeFrequency<I, T> {
+    /// Instantiate a new GreedyDual-Size-Frequency replacement policy, with a specifically
+    /// configured tiebreaker.
+    #[must_use]
+    pub fn with_tiebreaker(tiebreaker: T) -> Self {
+        Self {
+            value: HashMap::default(),
+            counts: HashMap::default(),
+            inflation: 0.0,
+            tiebreaker,
+        }
+    }
+}
+
+impl<I: Item, T: Tiebreaker<I>> ReplacementPolicy<I> for GreedyDualSizeFrequency<I, T> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        // values are compared per unit size, so they would be divided by zero
+        assert!(
+            next.size() > 0,
+            "GreedyDualSizeFrequency can't cache zero-size items."
+        );
+        let count = self.counts.entry(next).or_insert(0);
+        *count += 1;
+
+        self.value.insert(
+            next,
+            self.inflation + f64::from(*count) * next.cost() / f64::from(next.size()),
+        );
+        self.tiebreaker.update_state(set, capacity, next);
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.update_state(set, capacity, next);
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let to_evict = greedy_dual_evict(
+            &self.value,
+            &mut self.inflation,
+            &mut self.tiebreaker,
+            set,
+            capacity,
+        );
+
+        // frequencies only count accesses since the item entered the cache
+        for item in &to_evict {
+            self.value.remove(item);
+            self.counts.remove(item);
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.value.remove(&item);
+        self.counts.remove(&item);
+        self.tiebreaker.remove(item);
+    }
+
+    fn bypass(&mut self, item: I) {
+        self.tiebreaker.bypass(item);
+    }
+}
+
+/// The LRU-K replacement policy, which evicts the item whose K-th most recent access is oldest.
+///
+/// Detailed in this paper: <https://dl.acm.org/doi/10.1145/170036.170081>
+///
+/// Items which have been accessed fewer than K times have an infinite backward K-distance, so they
+/// are evicted first, in LRU order. The access history of evicted items is retained, so an item
+/// which comes back into the cache remembers its previous accesses. LRU-1 is just LRU.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, LruK};
+///
+/// let mut c = Cache::<LruK>::new(3);
+///
+/// c.access(0);
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(3);
+///
+/// // 1 and 2 have only been accessed once, so 1 is evicted even though 0 is less recent
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+#[derive(Clone)]
+pub struct LruK<I: Item = u32> {
+    history: HashMap<I, VecDeque<u64>>,
+    clock: u64,
+    k: usize,
+}
+
+impl<I: Item> Default for LruK<I> {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+impl<I: Item> LruK<I> {
+    /// Instantiate a new LRU-K replacement policy, which defaults to LRU-2.
+    ///
+    /// # Panics
+    ///
+    /// If `k` is zero.
+    #[must_use]
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0, "LRU-K needs at least one access to compare.");
+
+        Self {
+            history: HashMap::new(),
+            clock: 0,
+            k,
+        }
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for LruK<I> {
+    fn update_state(&mut self, _: &HashSet<I>, _: u32, next: I) {
+        let history = self.history.entry(next).or_default();
+
+        if history.len() == self.k {
+            history.pop_front();
+        }
+        history.push_back(self.clock);
+
+        self.clock += 1;
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.update_state(set, capacity, next);
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            let victim = *set
+                .iter()
+                .filter(|i| !to_evict.contains(*i))
+                .min_by_key(|i| {
+                    let history = &self.history[i];
+                    if history.len() == self.k {
+                        // the front of the history is the k-th most recent access
+                        (true, history[0])
+                    } else {
+                        // an infinite distance, with ties broken by the most recent access
+                        (false, history[history.len() - 1])
+                    }
+                })
+                .expect("The set is non-empty.");
+            to_evict.insert(victim);
+        }
+
+        to_evict
+    }
+}
+
+/// The status of an item tracked by [`ClockPro`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClockProStatus {
+    Hot,
+    Cold,
+    /// A non-resident cold item in its test period.
+    Test,
+}
+
+/// An entry in the [`ClockPro`] circular list.
+#[derive(Debug, Clone, Copy)]
+struct ClockProEntry<I: Item> {
+    item: I,
+    status: ClockProStatus,
+    referenced: bool,
+}
+
+/// The CLOCK-Pro replacement policy, a CLOCK-based approximation of [`Lirs`].
+///
+/// Detailed in this paper: <https://www.usenix.org/legacy/event/usenix05/tech/general/full_papers/jiang/jiang.pdf>
+///
+/// Items are hot or cold, and live in a single circular list along with non-resident cold items in
+/// their test period, which are no longer in the cache's set. Three hands sweep the list: the cold
+/// hand evicts unreferenced cold items, and promotes referenced ones to hot; the hot hand demotes
+/// unreferenced hot items to cold; and the test hand ends test periods, forgetting non-resident
+/// items. Re-accessing an item during its test period makes it hot, and grows the target for the
+/// number of cold items, while test periods which end without an access shrink it.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, ClockPro};
+///
+/// let mut c = Cache::<ClockPro>::new(3);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(0);
+/// c.access(3);
+///
+/// // 0 was referenced, so the cold hand promotes it and evicts 1 instead
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+#[derive(Default, Clone)]
+pub struct ClockPro<I: Item = u32> {
+    list: Vec<ClockProEntry<I>>,
+    hand_hot: usize,
+    hand_cold: usize,
+    hand_test: usize,
+    hot_size: u32,
+    cold_size: u32,
+    test_size: u32,
+    // the target size for cold items, which starts small and adapts to the workload; it's treated
+    // as at least one, so hot items never take up the whole cache
+    cold_target: u32,
+}
+
+impl<I: Item> ClockPro<I> {
+    /// Insert an entry just behind the hot hand, which is the head of the list.
+    fn insert(&mut self, entry: ClockProEntry<I>) {
+        let at = self.hand_hot;
+        self.list.insert(at, entry);
+
+        self.hand_hot += 1;
+        if self.hand_test >= at {
+            self.hand_test += 1;
+        }
+        // if the cold hand was with the hot hand, it now points at the new entry
+        if self.hand_cold > at {
+            self.hand_cold += 1;
+        }
+
+        self.wrap_hands();
+    }
+
+    /// Remove the entry at `index`; hands pointing at it move to the next entry.
+    fn remove_at(&mut self, index: usize) {
+        self.list.remove(index);
+
+        for hand in [&mut self.hand_hot, &mut self.hand_cold, &mut self.hand_test] {
+            if *hand > index {
+                *hand -= 1;
+            }
+        }
+
+        self.wrap_hands();
+    }
+
+    /// Bring any hands which have run off the end of the list back to the start.
+    fn wrap_hands(&mut self) {
+        let len = self.list.len();
+        for hand in [&mut self.hand_hot, &mut self.hand_cold, &mut self.hand_test] {
+            if *hand >= len {
+                *hand = 0;
+            }
+        }
+    }
+
+    /// Advance a hand to the next entry.
+    const fn advance(&self, hand: usize) -> usize {
+        (hand + 1) % self.list.len()
+    }
+
+    /// The size hot items may take up, which is whatever isn't set aside for cold items.
+    fn hot_target(&self, capacity: u32) -> u32 {
+        capacity.saturating_sub(self.cold_target.max(1))
+    }
+
+    /// Run the cold hand one step, evicting the cold item it points at if it's unreferenced.
+    fn run_hand_cold(&mut self, to_evict: &mut HashSet<I>) {
+        let entry = &mut self.list[self.hand_cold];
+        let size = entry.item.size();
+
+        if entry.status == ClockProStatus::Cold {
+            if entry.referenced {
+                // the item was accessed during its test period, so it becomes hot
+                entry.status = ClockProStatus::Hot;
+                entry.referenced = false;
+                self.cold_size -= size;
+                self.hot_size += size;
+            } else {
+                // evict the item, but remember it until its test period ends
+                entry.status = ClockProStatus::Test;
+                to_evict.insert(entry.item);
+                self.cold_size -= size;
+                self.test_size += size;
+            }
+        }
+
+        self.hand_cold = self.advance(self.hand_cold);
+    }
+
+    /// Run the hot hand one step, demoting the hot item it points at if it's unreferenced.
+    fn run_hand_hot(&mut self, to_evict: &mut HashSet<I>) {
+        // the hot hand ends the test periods of any items it passes
+        if self.hand_hot == self.hand_test {
+            self.run_hand_test(to_evict);
+        }
+
+        let entry = &mut self.list[self.hand_hot];
+        if entry.status == ClockProStatus::Hot {
+            if entry.referenced {
+                entry.referenced = false;
+            } else {
+                entry.status = ClockProStatus::Cold;
+                self.hot_size -= entry.item.size();
+                self.cold_size += entry.item.size();
+            }
+        }
+
+        self.hand_hot = self.advance(self.hand_hot);
+    }
+
+    /// Run the test hand one step, ending the test period of the item it points at.
+    fn run_hand_test(&mut self, to_evict: &mut HashSet<I>) {
+        // the test hand pushes the cold hand along ahead of it
+        if self.hand_test == self.hand_cold {
+            self.run_hand_cold(to_evict);
+        }
+
+        let entry = self.list[self.hand_test];
+        if entry.status == ClockProStatus::Test {
+            // removing the entry moves the hand on
+            self.remove_at(self.hand_test);
+            self.test_size -= entry.item.size();
+
+            // the item wasn't re-accessed in its test period, so we want fewer cold items
+            self.cold_target = self.cold_target.saturating_sub(entry.item.size()).max(1);
+        } else {
+            self.hand_test = self.advance(self.hand_test);
+        }
+    }
+
+    /// Run the hands until `size` more fits in the cache, returning the items to evict.
+    ///
+    /// The hands only ever run a single step, and all the balancing between them happens in this
+    /// loop, so it always terminates: no accesses happen while it runs, so reference bits are only
+    /// ever cleared, and every full sweep of a hand changes the status of some entry.
+    fn make_room(&mut self, set: &HashSet<I>, capacity: u32, size: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) + size > capacity && self.hot_size + self.cold_size > 0
+        {
+            if self.cold_size > 0 {
+                self.run_hand_cold(&mut to_evict);
+            } else {
+                // everything is hot, so something has to be demoted before it can be evicted
+                self.run_hand_hot(&mut to_evict);
+            }
+
+            while self.test_size > capacity {
+                self.run_hand_test(&mut to_evict);
+            }
+            while self.hot_size > self.hot_target(capacity) {
+                self.run_hand_hot(&mut to_evict);
+            }
+        }
+
+        to_evict
+    }
+
+    /// Handle an access to `next`, evicting items until it fits in the cache.
+    fn request(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let size = next.size();
+
+        let status = if let Some(index) = self.list.iter().position(|e| e.item == next) {
+            if self.list[index].status != ClockProStatus::Test {
+                self.list[index].referenced = true;
+                return HashSet::new();
+            }
+
+            // we're in the item's test period, so it becomes hot, and we want more cold items, but
+            // we always leave a tenth of the cache for hot items
+            self.cold_target = (self.cold_target + size).min(capacity - capacity / 10);
+
+            self.remove_at(index);
+            self.test_size -= size;
+            ClockProStatus::Hot
+        } else {
+            ClockProStatus::Cold
+        };
+
+        let to_evict = self.make_room(set, capacity, size);
+
+        self.insert(ClockProEntry {
+            item: next,
+            status,
+            referenced: false,
+        });
+        if status == ClockProStatus::Hot {
+            self.hot_size += size;
+        } else {
+            self.cold_size += size;
+        }
+
+        to_evict
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for ClockPro<I> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        let evicted = self.request(set, capacity, next);
+        debug_assert!(evicted.is_empty());
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        self.request(set, capacity, next)
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        self.cold_target = self.cold_target.min(capacity);
+        self.make_room(set, capacity, 0)
+    }
+
+    fn remove(&mut self, item: I) {
+        if let Some(index) = self.list.iter().position(|e| e.item == item) {
+            let entry = self.list[index];
+            match entry.status {
+                ClockProStatus::Hot => self.hot_size -= item.size(),
+                ClockProStatus::Cold => self.cold_size -= item.size(),
+                // non-resident items aren't in the cache, so they can't be removed from it
+                ClockProStatus::Test => return,
+            }
+            self.remove_at(index);
+        }
+    }
+}
+
+/// The metadata [`MultiQueue`] keeps for a resident item.
+#[derive(Debug, Clone, Copy)]
+struct MultiQueueEntry {
+    frequency: u32,
+    expiry: u64,
+    queue: usize,
+}
+
+/// The Multi-Queue (MQ) replacement policy.
+///
+/// Detailed in this paper: <https://www.usenix.org/legacy/event/usenix01/full_papers/zhou/zhou.pdf>
+///
+/// Items live in one of several LRU queues, with an item that's been accessed `f` times in queue
+/// `log2(f)`. Each access gives an item a fixed lifetime; an item at the front of its queue whose
+/// lifetime has expired is demoted to the queue below. The victim is the LRU item of the lowest
+/// non-empty queue. The access counts of evicted items are remembered in a history buffer, so an
+/// item which comes back into the cache picks up where it left off.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, MultiQueue};
+///
+/// let mut c = Cache::<MultiQueue>::new(3);
+///
+/// c.access(0);
+/// c.access(1);
+/// c.access(0);
+/// c.access(2);
+/// c.access(3);
+///
+/// // 0 has been accessed twice, so it's in a higher queue than 1
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+#[derive(Clone)]
+pub struct MultiQueue<I: Item = u32> {
+    queues: Vec<VecDeque<I>>,
+    entries: HashMap<I, MultiQueueEntry>,
+    history: VecDeque<(I, u32)>,
+    time: u64,
+    lifetime: u64,
+}
+
+impl<I: Item> Default for MultiQueue<I> {
+    fn default() -> Self {
+        Self::new(8, 100)
+    }
+}
+
+impl<I: Item> MultiQueue<I> {
+    /// Instantiate a new MQ replacement policy, with `num_queues` queues, where an item expires
+    /// `lifetime` accesses after it was last accessed. This defaults to 8 queues and a lifetime of
+    /// 100.
+    ///
+    /// # Panics
+    ///
+    /// If `num_queues` is zero.
+    #[must_use]
+    pub fn new(num_queues: usize, lifetime: u64) -> Self {
+        assert!(num_queues > 0, "MQ needs at least one queue.");
+
+        Self {
+            queues: vec![VecDeque::new(); num_queues],
+            entries: HashMap::new(),
+            history: VecDeque::new(),
+            time: 0,
+            lifetime,
+        }
+    }
+
+    /// Evict the LRU item of the lowest non-empty queue, remembering its access count.
+    fn evict(&mut self, capacity: u32) -> I {
+        let victim = self
+            .queues
+            .iter_mut()
+            .find_map(VecDeque::pop_front)
+            .expect("The cache is non-empty.");
+        let entry = self.entries.remove(&victim).expect("Victims are resident.");
+
+        // the paper suggests remembering four times as many items as fit in the cache
+        self.history.push_back((victim, entry.frequency));
+        while self.history.len() > 4 * capacity as usize {
+            self.history.pop_front();
+        }
+
+        victim
+    }
+
+    /// Demote the front item of each queue if its lifetime has expired.
+    fn adjust(&mut self) {
+        self.time += 1;
+
+        for k in 1..self.queues.len() {
+            let Some(&front) = self.queues[k].front() else {
+                continue;
+            };
+
+            let entry = self
+                .entries
+                .get_mut(&front)
+                .expect("Queued items are resident.");
+            if entry.expiry < self.time {
+                self.queues[k].pop_front();
+                self.queues[k - 1].push_back(front);
+                entry.queue = k - 1;
+                entry.expiry = self.time + self.lifetime;
+            }
+        }
+    }
+
+    /// Handle an access to `next`, evicting items until it fits in the cache.
+    fn request(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        let frequency = if let Some(entry) = self.entries.remove(&next) {
+            let queue = &mut self.queues[entry.queue];
+            if let Some(index) = queue.iter().position(|&i| i == next) {
+                queue.remove(index);
+            }
+            entry.frequency + 1
+        } else {
+            // look in the history before evicting, which might push next out of it
+            let remembered = self
+                .history
+                .iter()
+                .position(|&(i, _)| i == next)
+                .and_then(|index| self.history.remove(index))
+                .map_or(0, |(_, frequency)| frequency);
+
+            while set
+                .iter()
+                .filter(|i| !to_evict.contains(*i))
+                .map(Item::size)
+                .sum::<u32>()
+                + next.size()
+                > capacity
+            {
+                let victim = self.evict(capacity);
+                to_evict.insert(victim);
+            }
+
+            remembered + 1
+        };
+
+        let queue = (frequency.ilog2() as usize).min(self.queues.len() - 1);
+        self.queues[queue].push_back(next);
+        self.entries.insert(
+            next,
+            MultiQueueEntry {
+                frequency,
+                expiry: self.time + self.lifetime,
+                queue,
+            },
+        );
+
+        self.adjust();
+
+        to_evict
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for MultiQueue<I> {
+    fn update_state(&mut self, set: &HashSet<I>, capacity: u32, next: I) {
+        let evicted = self.request(set, capacity, next);
+        debug_assert!(evicted.is_empty());
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        self.request(set, capacity, next)
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            to_evict.insert(self.evict(capacity));
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        if let Some(entry) = self.entries.remove(&item) {
+            self.queues[entry.queue].retain(|&i| i != item);
+        }
+    }
+}
+
+/// The hyperbolic caching replacement policy, which evicts the item with the lowest access
+/// frequency since it was inserted.
+///
+/// Detailed in this paper: <https://www.usenix.org/conference/atc17/technical-sessions/presentation/blankstein>
+///
+/// An item's priority is the number of times it's been accessed, divided by the time since it was
+/// inserted, so new items aren't immediately evicted, and old popular items decay without the
+/// discrete aging of [`LfuAging`]. The priority is only computed on eviction, and forgotten once an
+/// item is evicted. Ties are broken by evicting the oldest item.
+///
+/// ```
+/// # use std::collections::HashSet;
+/// use cache_sim::{Cache, Hyperbolic};
+///
+/// let mut c = Cache::<Hyperbolic>::new(3);
+///
+/// c.access(0);
+/// c.access(0);
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(3);
+///
+/// // 0 has been accessed three times in five steps, which is more often than 1
+/// assert_eq!(c.set(), &HashSet::from([0, 2, 3]));
+/// ```
+#[derive(Default, Clone)]
+pub struct Hyperbolic<I: Item = u32> {
+    /// The access count and insertion time of each item in the cache.
+    entries: HashMap<I, (u32, u64)>,
+    clock: u64,
+}
+
+impl<I: Item> Hyperbolic<I> {
+    /// The priority of an item in the cache, and its insertion time to break ties.
+    #[allow(clippy::cast_precision_loss)] // the clock won't get anywhere near 2^52
+    fn priority(&self, item: &I) -> (f64, u64) {
+        let (count, inserted) = self.entries[item];
+        // the clock has ticked since any item in the cache was inserted, so this is positive
+        (f64::from(count) / (self.clock - inserted) as f64, inserted)
+    }
+}
+
+impl<I: Item> ReplacementPolicy<I> for Hyperbolic<I> {
+    fn update_state(&mut self, _: &HashSet<I>, _: u32, next: I) {
+        self.entries.entry(next).or_insert((0, self.clock)).0 += 1;
+        self.clock += 1;
+    }
+
+    fn replace(&mut self, set: &HashSet<I>, capacity: u32, next: I) -> HashSet<I> {
+        let to_evict = self.shrink(set, capacity.saturating_sub(next.size()));
+        self.update_state(set, capacity, next);
+        to_evict
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, capacity: u32) -> HashSet<I> {
+        let mut to_evict = HashSet::new();
+
+        while remaining_size(set, &to_evict) > capacity {
+            let victim = *set
+                .iter()
+                .filter(|i| !to_evict.contains(*i))
+                .min_by(|a, b| {
+                    self.priority(a)
+                        .partial_cmp(&self.priority(b))
+                        .expect("Priorities aren't NaN.")
+                })
+                .expect("The set is non-empty.");
+            to_evict.insert(victim);
+        }
+
+        for item in &to_evict {
+            self.entries.remove(item);
+        }
+
+        to_evict
+    }
+
+    fn remove(&mut self, item: I) {
+        self.entries.remove(&item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::HitCount;
+    use crate::Cache;
+
+    /// Count the hits of a cache with the given policy over a trace.
+    fn hits<R: ReplacementPolicy<u32> + Default>(capacity: u32, trace: &[u32]) -> u32 {
+        let mut c = Cache::<R, HitCount>::new(capacity);
+
+        for &item in trace {
+            c.access(item);
+        }
+
+        c.stat().0
+    }
+
+    macro_rules! integration_test {
+        ($name:ident ($policy:ty): $($in:expr),* => $($out:expr),*) => {
+            #[test]
+            fn $name() {
+                let mut c = Cache::<$policy>::new(3);
+
+                $(
+                    c.access($in);
+                )*
+
+                assert_eq!(c.set(), &HashSet::from([$($out),*]));
+            }
+        };
+    }
+
+    macro_rules! replacement_policy_test {
+        ($name:ident ($policy:ty):
+            counting_up => $($counting_up_out:expr),*;
+            repeated => $($repeated_out:expr),*;
+            one_repetition => $($one_repetition_out:expr),*;
+            cycle => $($cycle_out:expr),*;
+        ) => {
+            mod $name {
+                use super::*;
+
+                integration_test!(counting_up ($policy): 0, 1, 2, 3 => $($counting_up_out),*);
+                integration_test!(repeated ($policy): 0, 0, 0, 0 =>  $($repeated_out),*);
+                integration_test!(one_repetition ($policy): 0, 1, 2, 0, 3 => $($one_repetition_out),*);
+                integration_test!(cycle ($policy): 0, 1, 2, 0, 1, 2, 0, 1, 2, 3, 3 => $($cycle_out),*);
+            }
+        }
+    }
+
+    replacement_policy_test! {
+        lru (Lru):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    replacement_policy_test! {
+        mru (Mru):
+            counting_up => 0, 1, 3;
+            repeated => 0;
+            one_repetition => 1, 2, 3;
+            cycle => 0, 1, 3;
+    }
+
+    replacement_policy_test! {
+        fifo (Fifo):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 1, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    replacement_policy_test! {
+        fifo_reinsertion (FifoReinsertion):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod mru_workloads {
+        use super::*;
+        use crate::GeneralModelGenerator;
+
+        #[test]
+        fn evicts_until_next_fits() {
+            let mut gen = GeneralModelGenerator::new();
+            let a = gen.item(1.0, 2);
+            let b = gen.item(1.0, 1);
+            let c = gen.item(1.0, 2);
+
+            let mut cache = Cache::<Mru<_>, (), _>::new(3);
+            for item in [a, b, c] {
+                cache.access(item);
+            }
+
+            // evicting just b, the most recently used item, doesn't leave enough room for c
+            assert_eq!(cache.set(), &HashSet::from([c]));
+        }
+
+        #[test]
+        fn evicts_one_when_enough() {
+            let mut gen = GeneralModelGenerator::new();
+            let a = gen.item(1.0, 1);
+            let b = gen.item(1.0, 2);
+            let c = gen.item(1.0, 2);
+
+            let mut cache = Cache::<Mru<_>, (), _>::new(3);
+            for item in [a, b, c] {
+                cache.access(item);
+            }
+
+            assert_eq!(cache.set(), &HashSet::from([a, c]));
+        }
+    }
+
+    mod fifo_reinsertion_workloads {
+        use super::*;
+        use crate::GeneralModelGenerator;
+
+        #[test]
+        fn distinct_from_fifo_and_lru() {
+            let trace = [0, 1, 2, 1, 0, 3, 4];
+
+            let mut fifo = Cache::<Fifo>::new(3);
+            let mut lru = Cache::<Lru>::new(3);
+            let mut fifo_reinsertion = Cache::<FifoReinsertion>::new(3);
+            for &item in &trace {
+                fifo.access(item);
+                lru.access(item);
+                fifo_reinsertion.access(item);
+            }
+
+            assert_eq!(fifo.set(), &HashSet::from([2, 3, 4]));
+            assert_eq!(lru.set(), &HashSet::from([0, 3, 4]));
+            // 0 and 1 were reinserted in queue order, so 0 is evicted before 1
+            assert_eq!(fifo_reinsertion.set(), &HashSet::from([1, 3, 4]));
+        }
+
+        #[test]
+        fn size_aware() {
+            let mut cache = Cache::<FifoReinsertion<_>, (), _>::new(4);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 2);
+            let b = gen.item(1.0, 1);
+            let c = gen.item(1.0, 1);
+            let d = gen.item(1.0, 2);
+
+            cache.access(a);
+            cache.access(b);
+            cache.access(c);
+            cache.access(a);
+            cache.access(d);
+
+            // a is reinserted, then both b and c must go to make room for d
+            assert_eq!(cache.set(), &HashSet::from([a, d]));
+        }
+    }
+
+    mod rand {
+        use super::*;
+
+        #[test]
+        fn seeded_is_reproducible() {
+            let trace = [0, 1, 2, 3, 0, 4, 1, 5, 6, 2, 0, 7, 3, 8];
+
+            let mut a = Cache::<Rand<_>>::with_replacement_policy(Rand::seeded(17), 3_u32);
+            let mut b = Cache::<Rand<_>>::with_replacement_policy(Rand::seeded(17), 3_u32);
+            for &item in &trace {
+                a.access(item);
+                b.access(item);
+            }
+
+            assert_eq!(a.set(), b.set());
+        }
+
+        #[test]
+        fn thread_rng_by_default() {
+            let mut c = Cache::<Rand>::new(3);
+            for item in 0..10 {
+                c.access(item);
+            }
+
+            assert_eq!(c.set().len(), 3);
+        }
+    }
+
+    mod sampled_lru {
+        use super::*;
+
+        /// How many more or fewer hits sampled LRU gets than LRU on a skewed trace.
+        fn difference_from_lru(samples: usize) -> u32 {
+            let trace = Trace::zipf(200, 5000, 0.8, &mut StdRng::seed_from_u64(0));
+
+            let mut lru = Cache::<Lru, HitCount>::new(20);
+            lru.run_trace(&trace);
+
+            let mut sampled = Cache::<SampledLru<_, _>, HitCount>::with_replacement_policy(
+                SampledLru::seeded(1, samples),
+                20_u32,
+            );
+            sampled.run_trace(&trace);
+
+            lru.stat().0.abs_diff(sampled.stat().0)
+        }
+
+        #[test]
+        fn approaches_lru() {
+            let differences = [1, 2, 5, 20].map(difference_from_lru);
+
+            assert!(differences[0] > differences[1]);
+            assert!(differences[1] > differences[2]);
+            assert!(differences[2] > 0);
+            // sampling everything in the cache is just lru
+            assert_eq!(differences[3], 0);
+        }
+
+        #[test]
+        fn seeded_is_reproducible() {
+            let trace = Trace::from(vec![0, 1, 2, 3, 0, 4, 1, 5, 6, 2, 0, 7, 3, 8]);
+
+            let mut a = Cache::<SampledLru<_, _>>::with_replacement_policy(
+                SampledLru::seeded(17, 2),
+                3_u32,
+            );
+            let mut b = Cache::<SampledLru<_, _>>::with_replacement_policy(
+                SampledLru::seeded(17, 2),
+                3_u32,
+            );
+            a.run_trace(&trace);
+            b.run_trace(&trace);
+
+            assert_eq!(a.set(), b.set());
+        }
+    }
+
+    replacement_policy_test! {
+        lfu_aging (LfuAging):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod lfu_sizes {
+        use super::*;
+        use crate::{GeneralModelGenerator, GeneralModelItem};
+
+        /// Run a cache of capacity five where the incoming item needs more room than the least
+        /// frequently used item frees.
+        fn evicts_until_room<R: ReplacementPolicy<GeneralModelItem>>(policy: R) {
+            let mut cache = Cache::<R, (), _>::with_replacement_policy(policy, 5_u32);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 1);
+            let b = gen.item(1.0, 3);
+            let c = gen.item(1.0, 1);
+            let d = gen.item(1.0, 4);
+
+            for item in [a, a, a, b, c, c] {
+                cache.access(item);
+            }
+            cache.access(d);
+
+            // b is the least frequent, but evicting it leaves d one short, so c goes too
+            assert_eq!(cache.set(), &HashSet::from([a, d]));
+        }
+
+        #[test]
+        fn lfu() {
+            evicts_until_room(Lfu::<GeneralModelItem, Lru<_>>::default());
+        }
+
+        #[test]
+        fn lfu_aging() {
+            evicts_until_room(LfuAging::<GeneralModelItem, Lru<_>>::new(0.5, 100));
+        }
+    }
+
+    mod lfu_aging_workloads {
+        use super::*;
+
+        #[test]
+        fn stale_hot_item_is_forgotten() {
+            // 0 is hot early on, and then never accessed again
+            let mut trace = vec![0; 10];
+            trace.extend(1..=40);
+
+            let mut lfu = Cache::<Lfu>::new(2);
+            let mut lfu_aging =
+                Cache::<LfuAging>::with_replacement_policy(LfuAging::new(0.5, 10), 2_u32);
+            for &item in &trace {
+                lfu.access(item);
+                lfu_aging.access(item);
+            }
+
+            assert!(lfu.set().contains(&0));
+            assert!(!lfu_aging.set().contains(&0));
+        }
+    }
+
+    mod landlord {
+        use super::*;
+        use crate::GeneralModelGenerator;
+
+        #[test]
+        fn lru_tiebreaker() {
+            let mut cache = Cache::<Landlord, (), _>::new(3);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 1);
+            let b = gen.item(2.0, 2);
+            let c = gen.item(1.0, 1);
+
+            cache.access(a);
+            cache.access(b);
+            cache.access(c);
+
+            // should evict a because LRU
+            assert_eq!(cache.set(), &HashSet::from([b, c]));
+        }
+
+        #[test]
+        fn credit_not_cost() {
+            // we had a bug where we would compute delta with the cost, not the credit. this tests
+            // that that is fixed.
+            let mut cache = Cache::<Landlord, (), _>::new(3);
+            let mut gen = GeneralModelGenerator::new();
+
+            let itm_a = gen.item(2.0, 1);
+            let itm_b = gen.item(3.0, 1);
+            let itm_c = gen.item(3.0, 1);
+            let itm_z = gen.item(2.0, 1);
+            let itm_d = gen.item(1.0, 1);
 
             cache.access(itm_a);
             cache.access(itm_b);
@@ -475,7 +3706,1128 @@ mod tests {
             cache.access(itm_d);
             cache.access(itm_a);
 
-            assert_eq!(cache.set(), &HashSet::from([itm_a, itm_d, itm_z]));
+            assert_eq!(cache.set(), &HashSet::from([itm_a, itm_d, itm_z]));
+        }
+
+        #[test]
+        #[should_panic(expected = "Landlord can't cache zero-size items.")]
+        fn zero_size() {
+            let mut cache = Cache::<Landlord, (), _>::new(2);
+            let mut gen = GeneralModelGenerator::new();
+
+            cache.access(gen.item(1.0, 1));
+            cache.access(gen.item(1.0, 0));
+        }
+
+        #[test]
+        fn cost_rises_mid_trace() {
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 1);
+            let b = gen.item(2.0, 1);
+            let c = gen.item(1.0, 1);
+
+            let run = |a_later| {
+                let mut cache = Cache::<Landlord, (), _>::new(2);
+                for item in [a, b, a_later, c] {
+                    cache.access(item);
+                }
+                cache.set().clone()
+            };
+
+            // a is the cheapest, so it's evicted
+            assert_eq!(run(a), HashSet::from([b, c]));
+            // but once a's cost rises, its hit gives it more credit than b
+            assert_eq!(run(a.with_cost(10.0)), HashSet::from([a, c]));
+        }
+    }
+
+    mod evict_largest {
+        use super::*;
+        use crate::GeneralModelGenerator;
+
+        #[test]
+        fn lru_tiebreaker() {
+            let mut cache = Cache::<EvictLargest, (), _>::new(5);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 2);
+            let b = gen.item(1.0, 2);
+            let c = gen.item(1.0, 1);
+            let d = gen.item(1.0, 2);
+
+            cache.access(a);
+            cache.access(b);
+            cache.access(c);
+            cache.access(a);
+            cache.access(d);
+
+            // a and b are both largest, but b was used less recently
+            assert_eq!(cache.set(), &HashSet::from([a, c, d]));
+        }
+
+        #[test]
+        fn evicts_until_room() {
+            let mut cache = Cache::<EvictLargest, (), _>::new(4);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 1);
+            let b = gen.item(1.0, 1);
+            let c = gen.item(1.0, 2);
+            let d = gen.item(1.0, 3);
+
+            cache.access(a);
+            cache.access(b);
+            cache.access(c);
+            cache.access(d);
+
+            // evicting c isn't enough, so the least recently used of a and b goes too
+            assert_eq!(cache.set(), &HashSet::from([b, d]));
+        }
+    }
+
+    mod evict_cheapest {
+        use super::*;
+        use crate::GeneralModelGenerator;
+
+        #[test]
+        fn lru_tiebreaker() {
+            let mut cache = Cache::<EvictCheapest, (), _>::new(3);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 1);
+            let b = gen.item(1.0, 1);
+            let c = gen.item(2.0, 1);
+            let d = gen.item(1.0, 1);
+
+            cache.access(a);
+            cache.access(b);
+            cache.access(c);
+            cache.access(a);
+            cache.access(d);
+
+            // a and b are both cheapest, but b was used less recently
+            assert_eq!(cache.set(), &HashSet::from([a, c, d]));
+        }
+
+        #[test]
+        fn evicts_until_room() {
+            let mut cache = Cache::<EvictCheapest, (), _>::new(4);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(3.0, 2);
+            let b = gen.item(1.0, 1);
+            let c = gen.item(2.0, 1);
+            let d = gen.item(1.0, 2);
+
+            cache.access(a);
+            cache.access(b);
+            cache.access(c);
+            cache.access(d);
+
+            // evicting b isn't enough, so the next cheapest goes too
+            assert_eq!(cache.set(), &HashSet::from([a, d]));
+        }
+
+        #[test]
+        fn cost_rises_mid_trace() {
+            let mut cache = Cache::<EvictCheapest, (), _>::new(2);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 1);
+            let b = gen.item(2.0, 1);
+            let c = gen.item(1.0, 1);
+
+            cache.access(a);
+            cache.access(b);
+            cache.access(a.with_cost(10.0));
+            cache.access(c);
+
+            // the hit on a updated its cost, so b is now the cheapest
+            assert_eq!(cache.set(), &HashSet::from([a, c]));
+        }
+    }
+
+    replacement_policy_test! {
+        arc (Arc):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod arc_workloads {
+        use super::*;
+
+        #[test]
+        fn scan_resistant() {
+            // a hot loop, then a scan, then the hot loop again; lru forgets the loop during the
+            // scan, but arc keeps it in t2
+            let trace = [0, 1, 0, 1, 0, 1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0, 1];
+
+            assert_eq!(hits::<Lru>(4, &trace), 6);
+            assert_eq!(hits::<Arc>(4, &trace), 8);
+        }
+
+        #[test]
+        fn ghost_hit_adapts_target() {
+            let mut c = Cache::<Arc>::new(2);
+
+            c.access(0);
+            c.access(1);
+            c.access(0); // moves 0 to t2
+            c.access(2); // evicts 1 from t1 into b1
+            c.access(1); // ghost hit in b1, so t1 grows and we evict 0 from t2
+
+            assert_eq!(c.set(), &HashSet::from([1, 2]));
+        }
+    }
+
+    replacement_policy_test! {
+        clock (Clock):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod clock_workloads {
+        use super::*;
+
+        // the cycle pattern, but re-referenced in reverse before the miss; lru evicts the least
+        // recent item, while the clock hand clears every bit and falls back to insertion order
+        integration_test!(reversed_cycle_lru (Lru): 0, 1, 2, 0, 1, 2, 2, 1, 0, 3 => 0, 1, 3);
+        integration_test!(reversed_cycle_clock (Clock): 0, 1, 2, 0, 1, 2, 2, 1, 0, 3 => 1, 2, 3);
+
+        #[test]
+        fn sweeps_until_room() {
+            use crate::GeneralModelGenerator;
+
+            let mut cache = Cache::<Clock<_>, (), _>::new(3);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 1);
+            let b = gen.item(1.0, 1);
+            let c = gen.item(1.0, 1);
+            let d = gen.item(1.0, 2);
+
+            cache.access(a);
+            cache.access(b);
+            cache.access(c);
+            cache.access(a);
+            cache.access(d);
+
+            // a has a second chance, so both b and c have to go to make room for d
+            assert_eq!(cache.set(), &HashSet::from([a, d]));
+        }
+    }
+
+    replacement_policy_test! {
+        twoq (TwoQ):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 1, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod twoq_workloads {
+        use super::*;
+
+        #[test]
+        fn scan_in_hot_loop() {
+            // 0 and 1 are hot, but only 2q lets them survive the scan in the middle
+            let trace = [0, 1, 2, 3, 4, 0, 1, 5, 0, 1, 6, 7, 8, 9, 0, 1, 10, 11, 0, 1];
+
+            assert_eq!(hits::<Lru>(4, &trace), 4);
+            assert_eq!(hits::<Fifo>(4, &trace), 4);
+            assert_eq!(hits::<TwoQ>(4, &trace), 6);
+        }
+    }
+
+    mod lfu_ties {
+        use super::*;
+
+        #[test]
+        fn scan() {
+            // every item has been accessed once, including the evicted ones, so they all tie
+            let mut c = Cache::<Lfu>::new(2);
+            for i in 0..10 {
+                c.access(i);
+            }
+
+            assert_eq!(c.set().len(), 2);
+            assert!(c.set().contains(&9));
+        }
+    }
+
+    replacement_policy_test! {
+        slru (Slru):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod slru_segments {
+        use super::*;
+
+        #[test]
+        fn demotes_to_probationary() {
+            // the protected segment holds one item, so promoting 1 demotes 0
+            let mut c = Cache::<Slru>::with_replacement_policy(Slru::new(0.34), 3_u32);
+
+            for i in [0, 0, 2, 1, 1, 3] {
+                c.access(i);
+            }
+
+            // 0 was demoted to the top of probationary, above 2, so 2 was evicted instead
+            assert_eq!(c.set(), &HashSet::from([0, 1, 3]));
+        }
+
+        #[test]
+        fn evicts_protected_when_probationary_is_empty() {
+            let mut c = Cache::<Slru>::with_replacement_policy(Slru::new(1.0), 2_u32);
+
+            for i in [0, 0, 1, 1, 2] {
+                c.access(i);
+            }
+
+            // 0 and 1 are both protected, so the least recently used of them goes
+            assert_eq!(c.set(), &HashSet::from([1, 2]));
+        }
+    }
+
+    replacement_policy_test! {
+        lirs (Lirs):
+            counting_up => 0, 1, 3;
+            repeated => 0;
+            one_repetition => 0, 1, 3;
+            cycle => 0, 1, 3;
+    }
+
+    mod lirs_workloads {
+        use super::*;
+        use crate::AccessOutcome;
+
+        #[test]
+        fn loop_larger_than_cache() {
+            let trace = (0..10).flat_map(|_| 0..101).collect::<Vec<_>>();
+
+            // lru evicts every item just before it's needed again
+            assert_eq!(hits::<Lru>(100, &trace), 0);
+
+            // lirs keeps 99 lir items resident, so after the first pass it only misses on the two
+            // hir items
+            assert_eq!(hits::<Lirs>(100, &trace), 9 * 99);
+        }
+
+        #[test]
+        fn prunes_hir_items_from_bottom() {
+            let mut c = Cache::<Lirs>::new(3);
+
+            // accessing the bottom lir item 1 prunes 2 from the stack, so when 2 comes back after
+            // its eviction it's a brand new hir item, rather than being promoted to lir
+            for i in [0, 1, 2, 0, 1, 3, 2, 4] {
+                c.access(i);
+            }
+
+            assert_eq!(c.set(), &HashSet::from([0, 1, 4]));
+        }
+
+        #[test]
+        fn never_evicts_next_at_capacity_one() {
+            let mut c = Cache::<Lirs>::new(1);
+
+            // nothing fits in the lir space, so 0 is a non-resident hir item at the bottom of the
+            // stack when 1 is promoted
+            for i in [0, 1, 1] {
+                c.access(i);
+            }
+
+            assert_eq!(
+                c.access_outcome(0),
+                AccessOutcome::Miss {
+                    evicted: HashSet::from([1])
+                }
+            );
+            assert_eq!(c.set(), &HashSet::from([0]));
+        }
+    }
+
+    mod belady {
+        use super::*;
+        use crate::stats::MissCount;
+
+        fn misses<R: ReplacementPolicy<u32>>(policy: R, capacity: u32, trace: &Trace) -> u32 {
+            let mut c = Cache::<R, MissCount>::with_replacement_policy(policy, capacity);
+            c.run_trace(trace);
+            c.stat().0
+        }
+
+        #[test]
+        fn no_worse_than_lru() {
+            let traces = [
+                vec![0, 1, 2, 3],
+                vec![0, 0, 0, 0],
+                vec![0, 1, 2, 0, 3],
+                vec![0, 1, 2, 0, 1, 2, 0, 1, 2, 3, 3],
+                vec![0, 1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3],
+                vec![7, 0, 1, 2, 0, 3, 0, 4, 2, 3, 0, 3, 2, 1, 2, 0, 1, 7, 0, 1],
+            ];
+
+            for trace in traces.map(Trace::from) {
+                for capacity in 1..=4 {
+                    assert!(
+                        misses(Belady::new(&trace), capacity, &trace)
+                            <= misses(Lru::default(), capacity, &trace)
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn evicts_farthest_next_use() {
+            let trace = Trace::from(vec![0, 1, 2, 3, 0, 1]);
+            let mut c = Cache::<Belady>::with_replacement_policy(Belady::new(&trace), 3_u32);
+
+            for &item in trace.iter().take(4) {
+                c.access(item);
+            }
+
+            // 2 is never used again, so it has to go
+            assert_eq!(c.set(), &HashSet::from([0, 1, 3]));
+        }
+
+        #[test]
+        #[should_panic(expected = "Belady must be run on the trace it was constructed from.")]
+        fn wrong_trace() {
+            let trace = Trace::from(vec![0, 1, 2]);
+            let mut c = Cache::<Belady>::with_replacement_policy(Belady::new(&trace), 3_u32);
+
+            c.access(1);
+        }
+
+        #[test]
+        fn bypassed_accesses() {
+            let trace = Trace::from(vec![0, 1, 2, 3, 0, 1, 2, 3]);
+            let mut c =
+                Cache::<Belady, MissCount>::with_replacement_policy(Belady::new(&trace), 2_u32);
+
+            // the bypassed access still moves Belady along the trace
+            for (i, &item) in trace.iter().enumerate() {
+                if i == 2 {
+                    c.access_no_insert(item);
+                } else {
+                    c.access(item);
+                }
+            }
+
+            assert_eq!(c.stat().0, 6);
+        }
+    }
+
+    replacement_policy_test! {
+        wtinylfu (WTinyLfu):
+            counting_up => 0, 1, 3;
+            repeated => 0;
+            one_repetition => 0, 1, 3;
+            cycle => 0, 1, 3;
+    }
+
+    mod wtinylfu_workloads {
+        use super::*;
+
+        #[test]
+        fn one_hit_wonders() {
+            // a hot set, then a different hot set mixed with a scan of one-hit wonders
+            let mut trace = (0..20).flat_map(|_| 0..9).collect::<Vec<_>>();
+            for round in 0..40 {
+                trace.extend(20..29);
+                trace.extend((0..3).map(|i| 1000 + 3 * round + i));
+            }
+
+            // lfu holds on to the old hot set, but the aged sketch forgets it
+            let mut c = Cache::<WTinyLfu, HitCount>::with_replacement_policy(
+                WTinyLfu::new(16, 4, 0.01),
+                10_u32,
+            );
+            c.run_trace(&Trace::from(trace.clone()));
+
+            assert!(c.stat().0 > hits::<Lfu>(10, &trace));
+        }
+
+        #[test]
+        fn sketch_ages() {
+            let mut sketch = CountMinSketch::new(8, 2, 4);
+
+            for _ in 0..3 {
+                sketch.increment(&0);
+            }
+            assert_eq!(sketch.estimate(&0), 3);
+
+            // the fourth increment triggers aging, halving the counts
+            sketch.increment(&0);
+            assert_eq!(sketch.estimate(&0), 2);
+        }
+    }
+
+    replacement_policy_test! {
+        s3fifo (S3Fifo):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod s3fifo_workloads {
+        use super::*;
+
+        #[test]
+        fn cycle_then_scan() {
+            // the cycle pattern, interrupted by scans
+            let trace = [0, 1, 2, 0, 1, 2, 0, 1, 2, 3, 4, 0, 1, 2, 5, 6, 0, 1, 2];
+
+            assert_eq!(hits::<Lru>(3, &trace), 6);
+            assert_eq!(hits::<Fifo>(3, &trace), 6);
+            // the cycle moves to the main queue, and the scans are evicted from the small queue
+            assert_eq!(hits::<S3Fifo>(3, &trace), 10);
+        }
+
+        #[test]
+        fn ghost_hit_goes_to_main() {
+            let mut c = Cache::<S3Fifo>::new(2);
+
+            c.access(0);
+            c.access(1);
+            c.access(2); // evicts 0 into the ghost queue
+            c.access(0); // inserted into the main queue, evicting 1 from the small queue
+            c.access(3); // evicts 2 from the small queue
+            c.access(4); // evicts 3 from the small queue, rather than 0 from the main queue
+
+            assert_eq!(c.set(), &HashSet::from([0, 4]));
+        }
+    }
+
+    mod greedy_dual_size {
+        use super::*;
+        use crate::GeneralModelGenerator;
+
+        #[test]
+        fn inflation_ages_items() {
+            let mut cache = Cache::<GreedyDualSize, (), _>::new(2);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(3.0, 1);
+            let b = gen.item(2.0, 1);
+            let c = gen.item(2.0, 1);
+            let d = gen.item(2.0, 1);
+
+            cache.access(a); // 3
+            cache.access(b); // 2
+            cache.access(c); // evicts b, so inflation is 2 and c is 4
+            cache.access(d); // evicts a, because its value was set before the inflation
+
+            assert_eq!(cache.set(), &HashSet::from([c, d]));
+        }
+
+        #[test]
+        fn evicts_enough_for_large_items() {
+            let mut cache = Cache::<GreedyDualSize, (), _>::new(3);
+            let mut gen = GeneralModelGenerator::new();
+
+            let a = gen.item(1.0, 1);
+            let b = gen.item(1.0, 1);
+            let c = gen.item(5.0, 1);
+            let d = gen.item(1.0, 2);
+
+            cache.access(a);
+            cache.access(b);
+            cache.access(c);
+            cache.access(d);
+
+            assert_eq!(cache.set(), &HashSet::from([c, d]));
+        }
+
+        #[test]
+        #[should_panic(expected = "GreedyDualSize can't cache zero-size items.")]
+        fn zero_size() {
+            let mut cache = Cache::<GreedyDualSize, (), _>::new(2);
+            let mut gen = GeneralModelGenerator::new();
+
+            cache.access(gen.item(1.0, 1));
+            cache.access(gen.item(1.0, 0));
+        }
+    }
+
+    mod greedy_dual_size_frequency {
+        use super::*;
+        use crate::GeneralModelGenerator;
+
+        #[test]
+        fn keeps_small_frequent_items() {
+            let mut gen = GeneralModelGenerator::new();
+
+            let small = gen.item(1.0, 1);
+            let large = gen.item(6.0, 3);
+            let next = gen.item(1.0, 1);
+
+            let trace = Trace::from(vec![small, small, small, large, next]);
+
+            // per unit of size, the large item is more expensive, so gds evicts the small one
+            let mut gds = Cache::<GreedyDualSize, (), _>::new(4);
+            gds.run_trace(&trace);
+            assert_eq!(gds.set(), &HashSet::from([large, next]));
+
+            // but the small item is hit often enough that gdsf keeps it
+            let mut gdsf = Cache::<GreedyDualSizeFrequency, (), _>::new(4);
+            gdsf.run_trace(&trace);
+            assert_eq!(gdsf.set(), &HashSet::from([small, next]));
+        }
+
+        #[test]
+        fn frequency_resets_on_eviction() {
+            let mut cache = Cache::<GreedyDualSizeFrequency, (), _>::new(2);
+            let mut gen = GeneralModelGenerator::new();
+
+            let [a, b, c, d, e, f, g] = [(); 7].map(|_| gen.item(1.0, 1));
+
+            // a is accessed three times, but the inflation catches up with it and it's evicted by
+            // e; when it comes back its frequency starts from one, so it's evicted again by g
+            for item in [a, a, a, b, c, d, e, a, f, g] {
+                cache.access(item);
+            }
+
+            assert_eq!(cache.set(), &HashSet::from([f, g]));
+        }
+
+        #[test]
+        #[should_panic(expected = "GreedyDualSizeFrequency can't cache zero-size items.")]
+        fn zero_size() {
+            let mut cache = Cache::<GreedyDualSizeFrequency, (), _>::new(2);
+            let mut gen = GeneralModelGenerator::new();
+
+            cache.access(gen.item(1.0, 1));
+            cache.access(gen.item(1.0, 0));
+        }
+    }
+
+    replacement_policy_test! {
+        lru_k (LruK):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod lru_k_workloads {
+        use super::*;
+
+        #[test]
+        fn rereferenced_items_survive_scan() {
+            // 0 and 1 are each referenced twice in a row, then there's a scan
+            let trace = [0, 0, 1, 1, 2, 3, 4, 5, 0, 1];
+
+            assert_eq!(hits::<Lru>(3, &trace), 2);
+            assert_eq!(hits::<LruK>(3, &trace), 4);
+        }
+
+        #[test]
+        fn lru_1_is_lru() {
+            let trace = [0, 1, 2, 0, 3, 1, 4, 0, 2, 2, 3, 1];
+
+            let mut c = Cache::<LruK, HitCount>::with_replacement_policy(LruK::new(1), 3_u32);
+            for &item in &trace {
+                c.access(item);
+            }
+
+            assert_eq!(c.stat().0, hits::<Lru>(3, &trace));
+        }
+    }
+
+    replacement_policy_test! {
+        clock_pro (ClockPro):
+            counting_up => 0, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod clock_pro_workloads {
+        use super::*;
+        use crate::GeneralModelGenerator;
+
+        #[test]
+        fn loop_larger_than_cache() {
+            // a loop of 101 items through a cache of size 100, which defeats recency
+            let trace = (0..10).flat_map(|_| 0..101).collect::<Vec<_>>();
+
+            let clock_pro = hits::<ClockPro>(100, &trace);
+            let lirs = hits::<Lirs>(100, &trace);
+
+            assert_eq!(hits::<Clock>(100, &trace), 0);
+            assert!(clock_pro > lirs * 3 / 4);
+            assert!(clock_pro <= lirs);
+        }
+
+        #[test]
+        fn test_period_access_makes_hot() {
+            // 0 is re-accessed during its test period after being evicted, so it becomes hot and
+            // survives the following scan
+            let mut c = Cache::<ClockPro>::new(3);
+            for item in [0, 1, 2, 3, 0, 4, 5, 6] {
+                c.access(item);
+            }
+
+            assert!(c.set().contains(&0));
+        }
+
+        #[test]
+        fn capacity_one() {
+            // this used to recurse between the hands forever, since no room was left for hot items
+            let mut c = Cache::<ClockPro, HitCount>::new(1);
+            for item in [0, 0, 1] {
+                c.access(item);
+            }
+
+            assert_eq!(c.set(), &HashSet::from([1]));
+            assert_eq!(c.stat().0, 1);
+        }
+
+        #[test]
+        fn sized_items() {
+            let mut gen = GeneralModelGenerator::new();
+            let a = gen.item(1.0, 2);
+            let b = gen.item(1.0, 2);
+
+            // only one of the items fits at a time
+            let mut c = Cache::<ClockPro<_>, HitCount, _>::new(3);
+            for item in [a, b, a, b] {
+                c.access(item);
+            }
+
+            assert_eq!(c.set(), &HashSet::from([b]));
+            assert_eq!(c.stat().0, 0);
+        }
+    }
+
+    replacement_policy_test! {
+        multi_queue (MultiQueue):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod multi_queue_workloads {
+        use super::*;
+
+        #[test]
+        fn frequent_items_survive_scan() {
+            // 0 and 1 are accessed often, then there's a scan
+            let mut trace = vec![0, 1, 0, 1, 0, 1];
+            trace.extend(10..20);
+            trace.extend([0, 1]);
+
+            assert_eq!(hits::<Lru>(3, &trace), 4);
+            assert_eq!(hits::<MultiQueue>(3, &trace), 6);
+        }
+
+        #[test]
+        fn expired_items_are_demoted() {
+            // the same workload, but 0 and 1 expire during the scan, so they're evicted
+            let mut trace = vec![0, 1, 0, 1, 0, 1];
+            trace.extend(10..20);
+            trace.extend([0, 1]);
+
+            let mut c = Cache::<MultiQueue, HitCount>::with_replacement_policy(
+                MultiQueue::new(8, 2),
+                3_u32,
+            );
+            for &item in &trace {
+                c.access(item);
+            }
+
+            assert_eq!(c.stat().0, 4);
+        }
+
+        #[test]
+        fn history_remembers_frequency() {
+            // 0 is evicted with two accesses and comes back, so it's in a higher queue than 3
+            let mut c = Cache::<MultiQueue>::new(2);
+            for item in [0, 0, 1, 1, 2, 0, 3, 4] {
+                c.access(item);
+            }
+
+            assert_eq!(c.set(), &HashSet::from([0, 4]));
+        }
+    }
+
+    replacement_policy_test! {
+        hyperbolic (Hyperbolic):
+            counting_up => 1, 2, 3;
+            repeated => 0;
+            one_repetition => 0, 2, 3;
+            cycle => 1, 2, 3;
+    }
+
+    mod hyperbolic_workloads {
+        use super::*;
+
+        #[test]
+        fn frequent_items_survive_scan() {
+            // 0 is accessed often, then there's a short scan which pushes it out of lru
+            let trace = [0, 0, 0, 1, 2, 3, 0];
+
+            assert_eq!(hits::<Lru>(3, &trace), 2);
+            assert_eq!(hits::<Hyperbolic>(3, &trace), 3);
+        }
+
+        #[test]
+        fn old_popular_items_decay() {
+            // 0 was popular a long time ago, but now 1, 2, and 3 are in a loop
+            let mut trace = vec![0; 10];
+            trace.extend((0..10).flat_map(|_| [1, 2, 3]));
+
+            // lfu keeps 0 forever, so the loop never hits, but 0's priority decays until it's
+            // evicted
+            assert_eq!(hits::<Lfu>(3, &trace), 9);
+            assert_eq!(hits::<Hyperbolic>(3, &trace), 28);
+        }
+    }
+
+    mod shrink {
+        use super::*;
+
+        /// Check that a cache with the given policy keeps working after it shrinks and grows.
+        fn survives_resizing<R: ReplacementPolicy<u32>>(policy: R) {
+            let trace = [0, 1, 2, 0, 3, 4, 0, 1, 5, 6, 7, 0, 2, 8, 9, 1];
+            let mut c = Cache::<R>::with_replacement_policy(policy, 8_u32);
+
+            for &item in &trace {
+                c.access(item);
+            }
+
+            c.resize(3);
+            assert_eq!(c.set().len(), 3);
+
+            for &item in &trace {
+                c.access(item);
+            }
+            assert!(c.contains(1));
+
+            c.resize(1);
+            assert_eq!(c.set().len(), 1);
+
+            c.resize(8);
+            for &item in &trace {
+                c.access(item);
+            }
+            assert_eq!(c.set().len(), 8);
+        }
+
+        #[test]
+        fn all_policies_survive_resizing() {
+            survives_resizing(Lru::default());
+            survives_resizing(Fifo::default());
+            survives_resizing(FifoReinsertion::default());
+            survives_resizing(Rand::seeded(0));
+            survives_resizing(Mru::default());
+            survives_resizing(Lfu::<u32>::default());
+            survives_resizing(LfuAging::<u32>::default());
+            survives_resizing(Landlord::<u32, Lru>::default());
+            survives_resizing(EvictLargest::<u32, Lru>::default());
+            survives_resizing(EvictCheapest::<u32, Lru>::default());
+            survives_resizing(Arc::default());
+            survives_resizing(Clock::default());
+            survives_resizing(TwoQ::default());
+            survives_resizing(Slru::default());
+            survives_resizing(Lirs::default());
+            survives_resizing(WTinyLfu::default());
+            survives_resizing(S3Fifo::default());
+            survives_resizing(GreedyDualSize::<u32, Lru>::default());
+            survives_resizing(GreedyDualSizeFrequency::<u32, Lru>::default());
+            survives_resizing(LruK::default());
+            survives_resizing(ClockPro::default());
+            survives_resizing(MultiQueue::default());
+            survives_resizing(Hyperbolic::default());
+            survives_resizing(SampledLru::seeded(0, 5));
+        }
+
+        /// An LRU policy which doesn't override [`ReplacementPolicy::shrink`].
+        #[derive(Default)]
+        struct DefaultShrink {
+            lru: Lru,
+            keep_order: bool,
+        }
+
+        impl ReplacementPolicy<u32> for DefaultShrink {
+            fn update_state(&mut self, set: &HashSet<u32>, capacity: u32, next: u32) {
+                self.lru.update_state(set, capacity, next);
+            }
+
+            fn replace(&mut self, set: &HashSet<u32>, capacity: u32, next: u32) -> HashSet<u32> {
+                self.lru.replace(set, capacity, next)
+            }
+
+            fn remove(&mut self, item: u32) {
+                self.lru.remove(item);
+            }
+
+            fn order(&self) -> Vec<u32> {
+                if self.keep_order {
+                    self.lru.order()
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+
+        #[test]
+        fn default_follows_order() {
+            let policy = DefaultShrink {
+                keep_order: true,
+                ..DefaultShrink::default()
+            };
+            let mut c = Cache::<DefaultShrink>::with_replacement_policy(policy, 4_u32);
+            for item in [0, 1, 2, 3, 0] {
+                c.access(item);
+            }
+
+            c.resize(2);
+            assert_eq!(c.set(), &HashSet::from([0, 3]));
+
+            // the evicted items were removed from the policy, so it evicts the right item next
+            c.access(4);
+            assert_eq!(c.set(), &HashSet::from([0, 4]));
+        }
+
+        #[test]
+        fn default_without_order() {
+            survives_resizing(DefaultShrink::default());
+        }
+
+        #[test]
+        fn fifo_evicts_first_inserted() {
+            let mut c = Cache::<Fifo>::new(4);
+            for item in [0, 1, 2, 3, 0] {
+                c.access(item);
+            }
+
+            c.resize(2);
+            assert_eq!(c.set(), &HashSet::from([2, 3]));
+        }
+
+        #[test]
+        fn mru_evicts_most_recent() {
+            let mut c = Cache::<Mru>::new(4);
+            for item in [0, 1, 2, 3, 0] {
+                c.access(item);
+            }
+
+            c.resize(2);
+            assert_eq!(c.set(), &HashSet::from([1, 2]));
+        }
+
+        #[test]
+        fn lfu_evicts_least_frequent() {
+            let mut c = Cache::<Lfu>::new(4);
+            for item in [0, 0, 1, 1, 1, 2, 3, 3] {
+                c.access(item);
+            }
+
+            c.resize(2);
+            assert_eq!(c.set(), &HashSet::from([1, 3]));
+        }
+
+        #[test]
+        fn size_aware() {
+            let mut g = crate::GeneralModelGenerator::new();
+            let small = g.item(1.0, 1);
+            let large = g.item(1.0, 3);
+            let medium = g.item(1.0, 2);
+
+            let mut c = Cache::<EvictLargest, (), _>::new(6);
+            for item in [small, large, medium] {
+                c.access(item);
+            }
+
+            c.resize(3);
+            assert_eq!(c.set(), &HashSet::from([small, medium]));
+        }
+    }
+
+    mod remove {
+        use super::*;
+
+        /// Check that a cache with the given policy keeps working after items are invalidated.
+        fn survives_invalidation<R: ReplacementPolicy<u32>>(policy: R) {
+            let trace = [0, 1, 2, 0, 3, 4, 0, 1, 5, 6, 7, 0, 2, 8, 9, 1];
+            let mut c = Cache::<R>::with_replacement_policy(policy, 4_u32);
+
+            for (i, &item) in trace.iter().enumerate() {
+                c.access(item);
+                if i % 3 == 0 {
+                    assert!(c.invalidate(item));
+                }
+            }
+
+            for &item in &trace {
+                c.access(item);
+            }
+            assert_eq!(c.set().len(), 4);
+        }
+
+        #[test]
+        fn all_policies_survive_invalidation() {
+            survives_invalidation(Lru::default());
+            survives_invalidation(Fifo::default());
+            survives_invalidation(FifoReinsertion::default());
+            survives_invalidation(Rand::seeded(0));
+            survives_invalidation(Mru::default());
+            survives_invalidation(Lfu::<u32>::default());
+            survives_invalidation(LfuAging::<u32>::default());
+            survives_invalidation(Landlord::<u32, Lru>::default());
+            survives_invalidation(EvictLargest::<u32, Lru>::default());
+            survives_invalidation(EvictCheapest::<u32, Lru>::default());
+            survives_invalidation(Arc::default());
+            survives_invalidation(Clock::default());
+            survives_invalidation(TwoQ::default());
+            survives_invalidation(Slru::default());
+            survives_invalidation(Lirs::default());
+            survives_invalidation(WTinyLfu::default());
+            survives_invalidation(S3Fifo::default());
+            survives_invalidation(GreedyDualSize::<u32, Lru>::default());
+            survives_invalidation(GreedyDualSizeFrequency::<u32, Lru>::default());
+            survives_invalidation(LruK::default());
+            survives_invalidation(ClockPro::default());
+            survives_invalidation(MultiQueue::default());
+            survives_invalidation(Hyperbolic::default());
+            survives_invalidation(SampledLru::seeded(0, 5));
+        }
+
+        #[test]
+        fn lru_forgets_item() {
+            let mut lru = Lru::default();
+            let set = HashSet::from([0, 1, 2]);
+            for item in [0, 1, 2] {
+                lru.update_state(&set, 3, item);
+            }
+
+            lru.remove(1);
+            assert_eq!(lru.stack, vec![0, 2]);
+        }
+
+        #[test]
+        fn lfu_forgets_count() {
+            let mut c = Cache::<Lfu>::new(2);
+            for item in [0, 0, 0, 1] {
+                c.access(item);
+            }
+
+            // 0 comes back with a fresh count, so it's evicted before 1
+            c.invalidate(0);
+            c.access(0);
+            c.access(1);
+            c.access(2);
+            assert_eq!(c.set(), &HashSet::from([1, 2]));
+        }
+    }
+
+    mod order {
+        use super::*;
+
+        /// The order of a cache with the given policy after accessing the trace.
+        fn order<R: ReplacementPolicy<u32> + Default>(capacity: u32, trace: &[u32]) -> Vec<u32> {
+            let mut c = Cache::<R>::new(capacity);
+            for &item in trace {
+                c.access(item);
+            }
+
+            c.ordered_iter().copied().collect()
+        }
+
+        #[test]
+        fn mru_starts_with_most_recent() {
+            assert_eq!(order::<Mru>(3, &[0, 1, 2, 0]), vec![0, 2, 1]);
+        }
+
+        #[test]
+        fn clock_starts_at_hand() {
+            // the hand passes 0, clearing its bit, then evicts 1
+            assert_eq!(order::<Clock>(3, &[0, 1, 2, 0, 3]), vec![2, 0, 3]);
+        }
+
+        #[test]
+        fn slru_protected_last() {
+            assert_eq!(order::<Slru>(3, &[0, 0, 1, 2]), vec![1, 2, 0]);
+        }
+
+        #[test]
+        fn unordered_policies_include_everything() {
+            let mut items = order::<Lfu>(3, &[0, 1, 2, 0, 3]);
+            items.sort_unstable();
+            assert_eq!(items, vec![0, 2, 3]);
+        }
+    }
+
+    mod would_replace {
+        use super::*;
+        use crate::AccessOutcome;
+
+        const TRACE: [u32; 16] = [0, 1, 2, 0, 3, 4, 0, 1, 5, 6, 7, 0, 2, 8, 9, 1];
+
+        /// Check that the eviction candidate is always what the access actually evicts.
+        fn matches_access<R: ReplacementPolicy<u32> + Clone>(policy: R) {
+            let mut c = Cache::<R>::with_replacement_policy(policy, 3_u32);
+
+            for item in TRACE {
+                let candidate = c.eviction_candidate(item);
+                match c.access_outcome(item) {
+                    AccessOutcome::Hit => assert!(candidate.is_empty()),
+                    AccessOutcome::Miss { evicted } => assert_eq!(candidate, evicted),
+                }
+            }
+        }
+
+        #[test]
+        fn all_policies_match_access() {
+            matches_access(Lru::default());
+            matches_access(Fifo::default());
+            matches_access(FifoReinsertion::default());
+            matches_access(Rand::seeded(0));
+            matches_access(Mru::default());
+            matches_access(Lfu::<u32>::default());
+            matches_access(LfuAging::<u32>::default());
+            matches_access(Landlord::<u32, Lru>::default());
+            matches_access(EvictLargest::<u32, Lru>::default());
+            matches_access(EvictCheapest::<u32, Lru>::default());
+            matches_access(Arc::default());
+            matches_access(Clock::default());
+            matches_access(TwoQ::default());
+            matches_access(Slru::default());
+            matches_access(Lirs::default());
+            matches_access(Belady::new(&Trace::from(TRACE.to_vec())));
+            matches_access(WTinyLfu::default());
+            matches_access(S3Fifo::default());
+            matches_access(GreedyDualSize::<u32, Lru>::default());
+            matches_access(GreedyDualSizeFrequency::<u32, Lru>::default());
+            matches_access(LruK::default());
+            matches_access(ClockPro::default());
+            matches_access(MultiQueue::default());
+            matches_access(Hyperbolic::default());
+            matches_access(SampledLru::seeded(0, 5));
+        }
+
+        #[test]
+        fn candidate_doesnt_change_state() {
+            let mut c = Cache::<Lru>::new(2);
+            c.access(0);
+            c.access(1);
+
+            // asking about 2 shouldn't make it the most recently used item
+            assert_eq!(c.eviction_candidate(2), HashSet::from([0]));
+            assert_eq!(c.eviction_candidate(3), HashSet::from([0]));
+            assert_eq!(c.ordered_iter().copied().collect::<Vec<_>>(), vec![0, 1]);
         }
     }
 }
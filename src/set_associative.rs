@@ -0,0 +1,152 @@
+//! A set-associative cache.
+
+use std::collections::HashSet;
+
+use crate::cache::{AccessOutcome, Cache};
+use crate::item::Item;
+use crate::replacement_policy::ReplacementPolicy;
+use crate::stats::Stat;
+use crate::trace::Trace;
+
+/// A set-associative cache, which is split into a number of sets, each of which holds a fixed
+/// number of items, or ways.
+///
+/// Each item maps to the set given by its [`Item::id`] modulo the number of sets, and each set is
+/// an independent [`Cache`] with its own replacement policy. A fully associative cache is one with
+/// a single set.
+///
+/// The statistic is shared by all of the sets, and is updated with the contents of the set which
+/// the accessed item maps to.
+///
+/// ```
+/// use cache_sim::{Lru, SetAssociative, Trace};
+/// use cache_sim::stats::HitCount;
+///
+/// // 0 and 2 both map to the first set, so they evict each other
+/// let mut c = SetAssociative::<Lru, HitCount>::new(2, 1);
+/// c.run_trace(&Trace::from(vec![0, 1, 2, 1, 0]));
+///
+/// assert_eq!(c.stat().0, 1);
+/// ```
+pub struct SetAssociative<R: ReplacementPolicy<I>, S: Stat<I> = (), I: Item = u32> {
+    sets: Vec<Cache<R, (), I>>,
+    stat: S,
+}
+
+impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item> SetAssociative<R, S, I> {
+    /// Create an empty cache with `num_sets` sets of `ways` items each, where each set starts
+    /// with a copy of an explicitly configured replacement policy.
+    ///
+    /// # Panics
+    ///
+    /// If `num_sets` is zero.
+    pub fn with_replacement_policy(policy: R, num_sets: u32, ways: u32) -> Self
+    where
+        R: Clone,
+    {
+        assert!(num_sets > 0, "There must be at least one set.");
+
+        Self {
+            sets: std::iter::repeat_n(policy, num_sets as usize)
+                .map(|policy| Cache::with_replacement_policy(policy, ways))
+                .collect(),
+            stat: S::default(),
+        }
+    }
+
+    /// The index of the set which `item` maps to.
+    fn index(&self, item: I) -> usize {
+        item.id() as usize % self.sets.len()
+    }
+
+    /// Update the cache after an access to item.
+    ///
+    /// # Panics
+    ///
+    /// If the replacement policy errors, and so a set ends up over capacity.
+    pub fn access(&mut self, item: I) {
+        let index = self.index(item);
+        let set = &mut self.sets[index];
+        let before = set.set().clone();
+
+        let evicted = match set.access_outcome(item) {
+            AccessOutcome::Hit => HashSet::new(),
+            AccessOutcome::Miss { evicted } => evicted,
+        };
+
+        self.stat.update(&before, item, &evicted);
+    }
+
+    /// Update the cache after accessing all items in the trace.
+    pub fn run_trace(&mut self, trace: &Trace<I>) {
+        for item in trace {
+            self.access(*item);
+        }
+    }
+
+    /// Check whether `item` is in the cache.
+    pub fn contains(&self, item: I) -> bool {
+        self.sets[self.index(item)].contains(item)
+    }
+
+    /// Get a reference to the cache's statistic.
+    pub const fn stat(&self) -> &S {
+        &self.stat
+    }
+
+    /// Get a reference to each of the cache's sets.
+    pub fn sets(&self) -> &[Cache<R, (), I>] {
+        &self.sets
+    }
+}
+
+impl<R: ReplacementPolicy<I> + Default, S: Stat<I>, I: Item> SetAssociative<R, S, I> {
+    /// Create an empty cache with `num_sets` sets of `ways` items each, using the default
+    /// parameters for the replacement policy.
+    ///
+    /// # Panics
+    ///
+    /// If `num_sets` is zero.
+    #[must_use]
+    pub fn new(num_sets: u32, ways: u32) -> Self {
+        assert!(num_sets > 0, "There must be at least one set.");
+
+        Self {
+            sets: (0..num_sets).map(|_| Cache::new(ways)).collect(),
+            stat: S::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::MissCount;
+    use crate::Lru;
+
+    #[test]
+    fn conflict_misses() {
+        // 0, 4, and 8 all map to the first of four sets, which only holds two of them
+        let trace = Trace::from(vec![0, 4, 8, 0, 4, 8]);
+
+        let mut set_associative = SetAssociative::<Lru, MissCount>::new(4, 2);
+        set_associative.run_trace(&trace);
+
+        let mut fully_associative = Cache::<Lru, MissCount>::new(8);
+        fully_associative.run_trace(&trace);
+
+        assert_eq!(set_associative.stat().0, 6);
+        assert_eq!(fully_associative.stat().0, 3);
+    }
+
+    #[test]
+    fn sets_are_independent() {
+        let mut c = SetAssociative::<Lru>::new(2, 1);
+        c.run_trace(&Trace::from(vec![0, 1, 2]));
+
+        assert!(c.contains(1));
+        assert!(c.contains(2));
+        assert!(!c.contains(0));
+        assert_eq!(c.sets()[0].set(), &HashSet::from([2]));
+    }
+}
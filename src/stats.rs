@@ -1,8 +1,9 @@
 //! Implementations of statistics computed by the cache simulator.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::item::Item;
+use crate::trace::StackDistanceTracker;
 
 /// An abstract representation of a cache statistic.
 ///
@@ -14,8 +15,40 @@ use crate::item::Item;
 pub trait Stat<I: Item>: Default {
     /// Update the stat.
     ///
-    /// We get passed the set _before_ evictions occur.
+    /// We get passed the set _before_ evictions occur, along with every item which is about to be
+    /// evicted to make room for `next`. That can be more than one item if items have different
+    /// sizes, see [`EvictionCount`]; it's empty on hits, and on misses which don't evict anything.
     fn update(&mut self, set: &HashSet<I>, next: I, to_be_evicted: &HashSet<I>);
+
+    /// Update the stat after a miss on `next` which the admission policy didn't admit, so it
+    /// wasn't inserted into the cache and nothing was evicted.
+    ///
+    /// By default this is just a miss which doesn't evict anything, which is fine for stats which
+    /// don't care what's in the cache afterwards.
+    fn bypass(&mut self, set: &HashSet<I>, next: I) {
+        self.update(set, next, &HashSet::new());
+    }
+
+    /// Update the stat after the cache shrinks, evicting `evicted` without an access.
+    ///
+    /// We get passed the set _before_ evictions occur. By default this does nothing, which is fine
+    /// for stats which only care about accesses.
+    fn shrink(&mut self, _: &HashSet<I>, _: &HashSet<I>) {}
+
+    /// Update the stat after `item` is invalidated, i.e. removed from the cache from outside,
+    /// without an access or an eviction.
+    ///
+    /// By default this does nothing, which is fine for stats which only care about accesses.
+    fn invalidate(&mut self, _: I) {}
+
+    /// Forget everything the stat has seen, as when the cache is cleared, but keep its
+    /// configuration.
+    ///
+    /// By default this resets the stat to its default, which is right for stats without any
+    /// configuration.
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
 }
 
 /// The raw count of cache hits.
@@ -71,3 +104,905 @@ impl<I: Item> Stat<I> for MissCount {
         }
     }
 }
+
+/// The ratio of cache hits to total accesses, stored as the raw hit count and the total.
+///
+/// ```
+/// use cache_sim::{Cache, Lru};
+/// use cache_sim::stats::HitRatio;
+///
+/// let mut c = Cache::<Lru, HitRatio>::new(3);
+/// c.access(0); // miss
+/// c.access(1); // miss
+/// c.access(2); // miss
+/// c.access(0); // hit
+/// c.access(3); // miss
+/// c.access(0); // hit
+///
+/// assert_eq!(c.stat().ratio(), 2.0 / 6.0);
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct HitRatio(pub u32, pub u32);
+
+impl HitRatio {
+    /// The fraction of accesses which were hits, which is zero if there were no accesses.
+    #[must_use]
+    pub fn ratio(&self) -> f64 {
+        if self.1 == 0 {
+            0.0
+        } else {
+            f64::from(self.0) / f64::from(self.1)
+        }
+    }
+}
+
+impl<I: Item> Stat<I> for HitRatio {
+    fn update(&mut self, set: &HashSet<I>, next: I, _: &HashSet<I>) {
+        if set.contains(&next) {
+            self.0 += 1;
+        }
+        self.1 += 1;
+    }
+}
+
+/// The ratio of bytes hit to total bytes accessed, i.e. the hit ratio weighted by item size.
+///
+/// ```
+/// use cache_sim::{Cache, GeneralModelGenerator, Lru};
+/// use cache_sim::stats::{ByteHitRatio, HitRatio};
+///
+/// let mut c = Cache::<Lru<_>, (ByteHitRatio, HitRatio), _>::new(4);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let small = g.item(1.0, 1);
+/// let large = g.item(1.0, 3);
+///
+/// c.access(small); // miss
+/// c.access(large); // miss
+/// c.access(small); // hit
+/// c.access(small); // hit
+///
+/// assert_eq!(c.stat().0.ratio(), 2.0 / 6.0);
+/// assert_eq!(c.stat().1.ratio(), 2.0 / 4.0);
+/// ```
+#[derive(Default, Debug)]
+pub struct ByteHitRatio {
+    /// The total size of the items which were hits.
+    pub hit_bytes: u64,
+    /// The total size of all accessed items.
+    pub total_bytes: u64,
+}
+
+impl ByteHitRatio {
+    /// The fraction of bytes accessed which were hits, which is zero if there were no accesses.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // we'd need an absurdly long trace to lose precision
+    pub fn ratio(&self) -> f64 {
+        if self.total_bytes == 0 {
+            0.0
+        } else {
+            self.hit_bytes as f64 / self.total_bytes as f64
+        }
+    }
+}
+
+impl<I: Item> Stat<I> for ByteHitRatio {
+    fn update(&mut self, set: &HashSet<I>, next: I, _: &HashSet<I>) {
+        if set.contains(&next) {
+            self.hit_bytes += u64::from(next.size());
+        }
+        self.total_bytes += u64::from(next.size());
+    }
+}
+
+/// The total cost of all cache misses.
+///
+/// ```
+/// use cache_sim::{Cache, GeneralModelGenerator, Landlord};
+/// use cache_sim::stats::TotalMissCost;
+///
+/// let mut c = Cache::<Landlord, TotalMissCost, _>::new(3);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let a = g.item(1.0, 1);
+/// let b = g.item(0.5, 2);
+/// let d = g.item(100.0, 2);
+/// let e = g.item(1.0, 1);
+///
+/// c.access(a); // miss
+/// c.access(b); // miss
+/// c.access(d); // miss
+/// c.access(e); // miss
+/// c.access(d); // hit
+/// c.access(a); // miss
+///
+/// assert_eq!(c.stat().0, 103.5);
+/// ```
+#[derive(Default, Debug)]
+pub struct TotalMissCost(pub f64);
+
+impl<I: Item> Stat<I> for TotalMissCost {
+    fn update(&mut self, set: &HashSet<I>, next: I, _: &HashSet<I>) {
+        if !set.contains(&next) {
+            self.0 += next.cost();
+        }
+    }
+}
+
+/// The total number of items evicted, which can be more than one per access when items have
+/// different sizes.
+///
+/// ```
+/// use cache_sim::{Cache, GeneralModelGenerator, Landlord};
+/// use cache_sim::stats::EvictionCount;
+///
+/// let mut c = Cache::<Landlord, EvictionCount, _>::new(4);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// for _ in 0..4 {
+///     c.access(g.item(1.0, 1));
+/// }
+/// assert_eq!(c.stat().0, 0);
+///
+/// // this item takes up the whole cache, so everything else has to go
+/// c.access(g.item(1.0, 4));
+/// assert_eq!(c.stat().0, 4);
+///
+/// // shrinking the cache evicts items too
+/// c.resize(0);
+/// assert_eq!(c.stat().0, 5);
+/// ```
+#[derive(Default, Debug)]
+pub struct EvictionCount(pub u32);
+
+impl<I: Item> Stat<I> for EvictionCount {
+    #[allow(clippy::cast_possible_truncation)] // the capacity is a u32, so this fits
+    fn update(&mut self, _: &HashSet<I>, _: I, to_be_evicted: &HashSet<I>) {
+        self.0 += to_be_evicted.len() as u32;
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // the capacity is a u32, so this fits
+    fn shrink(&mut self, _: &HashSet<I>, evicted: &HashSet<I>) {
+        self.0 += evicted.len() as u32;
+    }
+}
+
+/// The count of hits and misses for each item.
+///
+/// ```
+/// use cache_sim::{Cache, Lru};
+/// use cache_sim::stats::PerItemHits;
+///
+/// let mut c = Cache::<Lru, PerItemHits<u32>>::new(2);
+/// c.access(0); // miss
+/// c.access(0); // hit
+/// c.access(1); // miss
+/// c.access(2); // miss
+/// c.access(0); // miss
+/// c.access(0); // hit
+///
+/// assert_eq!(c.stat().hits()[&0], 2);
+/// assert_eq!(c.stat().misses()[&0], 2);
+/// assert_eq!(c.stat().hits().get(&1), None);
+/// assert_eq!(c.stat().misses()[&1], 1);
+/// ```
+#[derive(Default, Debug)]
+pub struct PerItemHits<I: Item> {
+    hits: HashMap<I, u32>,
+    misses: HashMap<I, u32>,
+}
+
+impl<I: Item> PerItemHits<I> {
+    /// The number of hits for each item. Items which were never hit are absent.
+    #[must_use]
+    pub const fn hits(&self) -> &HashMap<I, u32> {
+        &self.hits
+    }
+
+    /// The number of misses for each item. Items which were never missed are absent.
+    #[must_use]
+    pub const fn misses(&self) -> &HashMap<I, u32> {
+        &self.misses
+    }
+}
+
+impl<I: Item> Stat<I> for PerItemHits<I> {
+    fn update(&mut self, set: &HashSet<I>, next: I, _: &HashSet<I>) {
+        if set.contains(&next) {
+            *self.hits.entry(next).or_insert(0) += 1;
+        } else {
+            *self.misses.entry(next).or_insert(0) += 1;
+        }
+    }
+}
+
+/// The count of compulsory misses, i.e. first accesses to each item.
+///
+/// Subtracting this from the [`MissCount`] gives the non-compulsory misses.
+///
+/// ```
+/// use cache_sim::{Cache, Lru};
+/// use cache_sim::stats::{ColdMissCount, MissCount};
+///
+/// let mut c = Cache::<Lru, (ColdMissCount<u32>, MissCount)>::new(2);
+/// c.access(0); // cold miss
+/// c.access(1); // cold miss
+/// c.access(2); // cold miss
+/// c.access(0); // miss
+/// c.access(3); // cold miss
+///
+/// assert_eq!(c.stat().0.count(), 4);
+/// assert_eq!(c.stat().1 .0 - c.stat().0.count(), 1);
+/// ```
+#[derive(Default, Debug)]
+pub struct ColdMissCount<I: Item> {
+    seen: HashSet<I>,
+    count: u32,
+}
+
+impl<I: Item> ColdMissCount<I> {
+    /// The number of compulsory misses.
+    #[must_use]
+    pub const fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+impl<I: Item> Stat<I> for ColdMissCount<I> {
+    fn update(&mut self, _: &HashSet<I>, next: I, _: &HashSet<I>) {
+        if self.seen.insert(next) {
+            self.count += 1;
+        }
+    }
+}
+
+/// A wrapper around a statistic which ignores the first `skip` accesses, e.g. while the cache
+/// warms up.
+///
+/// ```
+/// use cache_sim::{Cache, Lru};
+/// use cache_sim::stats::{HitCount, Warmup};
+///
+/// let mut c = Cache::<Lru, Warmup<HitCount>>::new(3).with_stat(Warmup::new(3));
+/// c.access(0); // miss, ignored
+/// c.access(0); // hit, ignored
+/// c.access(0); // hit, ignored
+/// c.access(0); // hit
+/// c.access(1); // miss
+///
+/// assert_eq!(c.stat().inner().0, 1);
+/// ```
+#[derive(Default, Debug)]
+pub struct Warmup<S> {
+    inner: S,
+    skip: usize,
+    seen: usize,
+}
+
+impl<S: Default> Warmup<S> {
+    /// Instantiate a new warmup wrapper, which ignores the first `skip` accesses.
+    #[must_use]
+    pub fn new(skip: usize) -> Self {
+        Self {
+            inner: S::default(),
+            skip,
+            seen: 0,
+        }
+    }
+}
+
+impl<S> Warmup<S> {
+    /// Get a reference to the wrapped statistic.
+    pub const fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<I: Item, S: Stat<I>> Stat<I> for Warmup<S> {
+    fn update(&mut self, set: &HashSet<I>, next: I, to_be_evicted: &HashSet<I>) {
+        if self.seen < self.skip {
+            self.seen += 1;
+        } else {
+            self.inner.update(set, next, to_be_evicted);
+        }
+    }
+
+    fn bypass(&mut self, set: &HashSet<I>, next: I) {
+        if self.seen < self.skip {
+            self.seen += 1;
+        } else {
+            self.inner.bypass(set, next);
+        }
+    }
+
+    fn shrink(&mut self, set: &HashSet<I>, evicted: &HashSet<I>) {
+        if self.seen >= self.skip {
+            self.inner.shrink(set, evicted);
+        }
+    }
+
+    fn invalidate(&mut self, item: I) {
+        self.inner.invalidate(item);
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.seen = 0;
+    }
+}
+
+/// The hit ratio over consecutive, non-overlapping windows of accesses.
+///
+/// ```
+/// use cache_sim::{Cache, Lru};
+/// use cache_sim::stats::WindowedHitRatio;
+///
+/// let mut c = Cache::<Lru, WindowedHitRatio>::new(3);
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+///
+/// // start measuring once the cache is full
+/// let mut c = c.with_stat(WindowedHitRatio::new(3));
+/// c.access(0); // hit
+/// c.access(1); // hit
+/// c.access(2); // hit
+/// c.access(3); // miss
+/// c.access(4); // miss
+/// c.access(5); // miss
+///
+/// assert_eq!(c.stat().windows(), &[1.0, 0.0]);
+///
+/// // the last window is only partially full, so we have to finish it by hand
+/// c.access(5); // hit
+/// let mut stat = c.stat().clone();
+/// stat.finish();
+///
+/// assert_eq!(stat.windows(), &[1.0, 0.0, 1.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowedHitRatio {
+    window: usize,
+    current: HitRatio,
+    windows: Vec<f64>,
+}
+
+impl Default for WindowedHitRatio {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl WindowedHitRatio {
+    /// Instantiate a new windowed hit ratio, with windows of `window` accesses. This defaults to
+    /// 100.
+    ///
+    /// # Panics
+    ///
+    /// If `window` is zero.
+    #[must_use]
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "Windows must contain at least one access.");
+
+        Self {
+            window,
+            current: HitRatio::default(),
+            windows: Vec::new(),
+        }
+    }
+
+    /// The hit ratios of each completed window, in order.
+    #[must_use]
+    pub fn windows(&self) -> &[f64] {
+        &self.windows
+    }
+
+    /// Complete the current window, even if it's not full yet. This does nothing if the current
+    /// window is empty.
+    pub fn finish(&mut self) {
+        if self.current.1 > 0 {
+            self.windows.push(self.current.ratio());
+            self.current = HitRatio::default();
+        }
+    }
+}
+
+impl<I: Item> Stat<I> for WindowedHitRatio {
+    fn update(&mut self, set: &HashSet<I>, next: I, to_be_evicted: &HashSet<I>) {
+        self.current.update(set, next, to_be_evicted);
+
+        if self.current.1 as usize == self.window {
+            self.finish();
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.window);
+    }
+}
+
+/// The stack distance of each access, computed online as the cache runs.
+///
+/// This is the same as [`Trace::stack_distances`](crate::Trace::stack_distances), without needing
+/// the whole trace up front, using a [`StackDistanceTracker`]. Infinities are represented by
+/// `None`; finite distances by `Some(n)`.
+///
+/// ```
+/// use cache_sim::{Cache, Lru, Trace};
+/// use cache_sim::stats::ReuseDistances;
+///
+/// let trace = Trace::from(vec![0, 0, 1, 0, 3, 0, 1]);
+///
+/// let mut c = Cache::<Lru, ReuseDistances<u32>>::new(2);
+/// c.run_trace(&trace);
+///
+/// assert_eq!(c.stat().distances(), trace.stack_distances().inner());
+/// assert_eq!(c.stat().histogram(), trace.stack_distances().histogram());
+/// ```
+#[derive(Default, Debug)]
+pub struct ReuseDistances<I: Item> {
+    tracker: StackDistanceTracker<I>,
+    distances: Vec<Option<u32>>,
+}
+
+impl<I: Item> ReuseDistances<I> {
+    /// Get a reference to the distances so far.
+    ///
+    /// The ith element of the vector is the ith access.
+    #[must_use]
+    pub fn distances(&self) -> &[Option<u32>] {
+        &self.distances
+    }
+
+    /// Calculate the stack distance histogram.
+    ///
+    /// Returns a vector of frequencies of stack distances, plus the count of infinities.
+    #[must_use]
+    pub fn histogram(&self) -> (Vec<usize>, usize) {
+        crate::trace::distance_histogram(&self.distances)
+    }
+}
+
+impl<I: Item> Stat<I> for ReuseDistances<I> {
+    fn update(&mut self, _: &HashSet<I>, next: I, _: &HashSet<I>) {
+        self.distances.push(self.tracker.push(next));
+    }
+}
+
+/// The most capacity the items in the cache have used at once.
+///
+/// ```
+/// use cache_sim::{Cache, EvictLargest, GeneralModelGenerator};
+/// use cache_sim::stats::PeakOccupancy;
+///
+/// let mut c = Cache::<EvictLargest, PeakOccupancy, _>::new(5);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// c.access(g.item(1.0, 2));
+/// c.access(g.item(1.0, 3)); // the cache is full
+/// c.access(g.item(1.0, 4)); // evicts both other items
+///
+/// assert_eq!(c.stat().0, 5);
+/// ```
+///
+/// Items which the admission policy doesn't admit never take up any capacity:
+///
+/// ```
+/// use cache_sim::{Cache, GeneralModelGenerator, Lru};
+/// use cache_sim::admission::AdmitRepeated;
+/// use cache_sim::stats::PeakOccupancy;
+///
+/// let mut c = Cache::<Lru<_>, PeakOccupancy, _, AdmitRepeated<_>>::new(5);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let item = g.item(1.0, 2);
+/// c.access(item);
+/// assert_eq!(c.stat().0, 0);
+///
+/// c.access(item); // admitted on its second access
+/// assert_eq!(c.stat().0, 2);
+/// ```
+///
+/// Items which leave the cache without an access, because it's resized or they're invalidated,
+/// free up their capacity:
+///
+/// ```
+/// use cache_sim::{Cache, GeneralModelGenerator, Lru};
+/// use cache_sim::stats::PeakOccupancy;
+///
+/// let mut c = Cache::<Lru<_>, PeakOccupancy, _>::new(5);
+/// let mut g = GeneralModelGenerator::new();
+///
+/// let item = g.item(1.0, 3);
+/// c.access(item);
+/// c.access(g.item(1.0, 2));
+/// c.invalidate(item);
+///
+/// c.resize(6);
+/// c.access(g.item(1.0, 4));
+/// assert_eq!(c.stat().0, 6);
+/// ```
+#[derive(Default, Debug)]
+pub struct PeakOccupancy(
+    pub u32,
+    /// The current occupancy, which we only know once we've seen the set; the stat might be
+    /// installed in a cache which isn't empty.
+    Option<u32>,
+);
+
+impl<I: Item> Stat<I> for PeakOccupancy {
+    fn update(&mut self, set: &HashSet<I>, next: I, to_be_evicted: &HashSet<I>) {
+        let occupancy = self
+            .1
+            .get_or_insert_with(|| set.iter().map(Item::size).sum());
+
+        // we get the set before the access, so we have to account for the evictions and for next
+        // being inserted ourselves; on a hit, next replaces the stored access, whose size might
+        // be different
+        *occupancy -= to_be_evicted.iter().map(Item::size).sum::<u32>();
+        *occupancy -= set.get(&next).map_or(0, Item::size);
+        *occupancy += next.size();
+
+        self.0 = self.0.max(*occupancy);
+    }
+
+    fn bypass(&mut self, _: &HashSet<I>, _: I) {
+        // the cache doesn't change, so neither does its occupancy
+    }
+
+    fn shrink(&mut self, _: &HashSet<I>, evicted: &HashSet<I>) {
+        if let Some(occupancy) = &mut self.1 {
+            *occupancy -= evicted.iter().map(Item::size).sum::<u32>();
+        }
+    }
+
+    fn invalidate(&mut self, item: I) {
+        if let Some(occupancy) = &mut self.1 {
+            *occupancy -= item.size();
+        }
+    }
+}
+
+/// The average number of accesses for which evicted items stayed in the cache.
+///
+/// ```
+/// use cache_sim::{Cache, Lru};
+/// use cache_sim::stats::AverageResidency;
+///
+/// let mut c = Cache::<Lru, AverageResidency<u32>>::new(2);
+/// c.access(0);
+/// c.access(1);
+/// c.access(0);
+/// c.access(2); // evicts 1, which was inserted two accesses ago
+/// c.access(1); // evicts 0, which was inserted four accesses ago
+///
+/// assert_eq!(c.stat().average(), 3.0);
+///
+/// // shrinking the cache evicts 2 and 1, which were inserted two accesses and one access ago
+/// c.resize(0);
+/// assert_eq!(c.stat().average(), 2.25);
+/// ```
+#[derive(Default, Debug)]
+pub struct AverageResidency<I: Item> {
+    insert_time: HashMap<I, u64>,
+    clock: u64,
+    total: u64,
+    evictions: u64,
+}
+
+impl<I: Item> AverageResidency<I> {
+    /// The average residency time of evicted items, which is zero if nothing was evicted.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // we'd need an absurdly long trace to lose precision
+    pub fn average(&self) -> f64 {
+        if self.evictions == 0 {
+            0.0
+        } else {
+            self.total as f64 / self.evictions as f64
+        }
+    }
+
+    /// Record the residency times of evicted items.
+    fn record_evictions(&mut self, evicted: &HashSet<I>) {
+        // items inserted before this stat was installed have no known insertion time, so we skip
+        // them
+        for inserted in evicted.iter().filter_map(|i| self.insert_time.remove(i)) {
+            self.total += self.clock - inserted;
+            self.evictions += 1;
+        }
+    }
+}
+
+impl<I: Item> Stat<I> for AverageResidency<I> {
+    fn update(&mut self, set: &HashSet<I>, next: I, to_be_evicted: &HashSet<I>) {
+        self.record_evictions(to_be_evicted);
+
+        if !set.contains(&next) {
+            self.insert_time.insert(next, self.clock);
+        }
+
+        self.clock += 1;
+    }
+
+    fn bypass(&mut self, _: &HashSet<I>, _: I) {
+        // the item isn't inserted, so it never gets evicted, but the access still takes time
+        self.clock += 1;
+    }
+
+    fn shrink(&mut self, _: &HashSet<I>, evicted: &HashSet<I>) {
+        self.record_evictions(evicted);
+    }
+
+    fn invalidate(&mut self, item: I) {
+        // the item wasn't evicted, so it doesn't count towards the average
+        self.insert_time.remove(&item);
+    }
+}
+
+/// The count of misses on items which were evicted within the last `window` accesses, i.e. the
+/// misses caused by the cache being polluted with items that weren't worth keeping.
+///
+/// ```
+/// use cache_sim::{Cache, Lru};
+/// use cache_sim::stats::Pollution;
+///
+/// let mut c = Cache::<Lru, Pollution<u32>>::new(3).with_stat(Pollution::new(5));
+/// c.access(0);
+/// c.access(0);
+/// c.access(1);
+/// c.access(2);
+/// c.access(3); // the scan evicts 0
+/// c.access(4);
+/// c.access(5);
+/// c.access(0); // three accesses later, 0 misses
+///
+/// assert_eq!(c.stat().count(), 1);
+/// ```
+#[derive(Debug)]
+pub struct Pollution<I: Item> {
+    evicted_recently: HashMap<I, u64>,
+    clock: u64,
+    misses: u32,
+    window: u64,
+}
+
+impl<I: Item> Default for Pollution<I> {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl<I: Item> Pollution<I> {
+    /// Instantiate a new pollution statistic, counting misses on items evicted within the last
+    /// `window` accesses. This defaults to 100.
+    #[must_use]
+    pub fn new(window: u64) -> Self {
+        Self {
+            evicted_recently: HashMap::new(),
+            clock: 0,
+            misses: 0,
+            window,
+        }
+    }
+
+    /// The number of misses on recently evicted items.
+    #[must_use]
+    pub const fn count(&self) -> u32 {
+        self.misses
+    }
+
+    /// The number of accesses for which an evicted item counts as recently evicted.
+    #[must_use]
+    pub const fn window(&self) -> u64 {
+        self.window
+    }
+}
+
+impl<I: Item> Stat<I> for Pollution<I> {
+    fn update(&mut self, set: &HashSet<I>, next: I, to_be_evicted: &HashSet<I>) {
+        if !set.contains(&next) {
+            if let Some(evicted) = self.evicted_recently.remove(&next) {
+                if self.clock - evicted <= self.window {
+                    self.misses += 1;
+                }
+            }
+        }
+
+        for &item in to_be_evicted {
+            self.evicted_recently.insert(item, self.clock);
+        }
+
+        self.clock += 1;
+    }
+
+    fn shrink(&mut self, _: &HashSet<I>, evicted: &HashSet<I>) {
+        for &item in evicted {
+            self.evicted_recently.insert(item, self.clock);
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.window);
+    }
+}
+
+/// How writes reach the backing store, for [`WriteBacks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Every write goes straight to the backing store.
+    WriteThrough,
+    /// Writes only mark the item as dirty, and dirty items are written to the backing store when
+    /// they're evicted.
+    WriteBack,
+}
+
+/// The count of writes to the backing store, under either write policy.
+///
+/// Writes are accesses to items for which [`Item::is_write`] is true, like the writes in an ATF
+/// trace. Missed writes are allocated in the cache like any other access, unless the admission
+/// policy doesn't admit them, in which case they go straight to the backing store.
+///
+/// ```
+/// use cache_sim::{Cache, GeneralModelItem, Lru, Trace};
+/// use cache_sim::admission::AdmitRepeated;
+/// use cache_sim::stats::{WriteBacks, WritePolicy};
+///
+/// let read = |uid| GeneralModelItem::new(uid, 1.0, 1);
+/// let write = |uid| read(uid).as_write();
+///
+/// let trace = Trace::from(vec![write(0), read(1), read(2), write(1), read(0), read(2)]);
+///
+/// // 0 and 1 are each written back once, when they're evicted
+/// let mut c = Cache::<Lru<_>, WriteBacks<_>, _>::new(2);
+/// c.run_trace(&trace);
+/// assert_eq!(c.stat().count(), 2);
+/// assert_eq!(c.stat().dirty(), 0);
+///
+/// // both writes go straight to the backing store
+/// let mut c = Cache::<Lru<_>, WriteBacks<_>, _>::new(2)
+///     .with_stat(WriteBacks::new(WritePolicy::WriteThrough));
+/// c.run_trace(&trace);
+/// assert_eq!(c.stat().count(), 2);
+///
+/// // with write-back, the first write to each item isn't admitted, so it's written through
+/// let trace = Trace::from(vec![write(0), write(0), read(1), read(1), read(2), read(2)]);
+/// let mut c = Cache::<Lru<_>, WriteBacks<_>, _, AdmitRepeated<_>>::new(1);
+/// c.run_trace(&trace);
+/// assert_eq!(c.stat().count(), 2);
+/// assert_eq!(c.stat().dirty(), 0);
+///
+/// // shrinking the cache writes back the dirty items it evicts, but invalidated items are stale,
+/// // so they're dropped
+/// let mut c = Cache::<Lru<_>, WriteBacks<_>, _>::new(2);
+/// c.run_trace(&Trace::from(vec![write(0), write(1)]));
+/// c.invalidate(read(1));
+/// c.resize(0);
+/// assert_eq!(c.stat().count(), 1);
+/// assert_eq!(c.stat().dirty(), 0);
+/// ```
+#[derive(Debug)]
+pub struct WriteBacks<I: Item> {
+    policy: WritePolicy,
+    dirty: HashSet<I>,
+    count: u32,
+}
+
+impl<I: Item> Default for WriteBacks<I> {
+    fn default() -> Self {
+        Self::new(WritePolicy::WriteBack)
+    }
+}
+
+impl<I: Item> WriteBacks<I> {
+    /// Instantiate a new write-back statistic with the given write policy. This defaults to
+    /// write-back.
+    #[must_use]
+    pub fn new(policy: WritePolicy) -> Self {
+        Self {
+            policy,
+            dirty: HashSet::new(),
+            count: 0,
+        }
+    }
+
+    /// The number of writes to the backing store.
+    #[must_use]
+    pub const fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// The number of dirty items in the cache, which haven't been written back yet.
+    #[must_use]
+    pub fn dirty(&self) -> usize {
+        self.dirty.len()
+    }
+
+    /// The write policy.
+    #[must_use]
+    pub const fn policy(&self) -> WritePolicy {
+        self.policy
+    }
+
+    /// Write back any dirty items which are being evicted.
+    fn write_back(&mut self, evicted: &HashSet<I>) {
+        for item in evicted {
+            if self.dirty.remove(item) {
+                self.count += 1;
+            }
+        }
+    }
+}
+
+impl<I: Item> Stat<I> for WriteBacks<I> {
+    fn update(&mut self, _: &HashSet<I>, next: I, to_be_evicted: &HashSet<I>) {
+        self.write_back(to_be_evicted);
+
+        if next.is_write() {
+            match self.policy {
+                WritePolicy::WriteThrough => self.count += 1,
+                WritePolicy::WriteBack => {
+                    self.dirty.insert(next);
+                }
+            }
+        }
+    }
+
+    fn bypass(&mut self, _: &HashSet<I>, next: I) {
+        // the item isn't cached, so there's nothing to mark dirty
+        if next.is_write() {
+            self.count += 1;
+        }
+    }
+
+    fn shrink(&mut self, _: &HashSet<I>, evicted: &HashSet<I>) {
+        self.write_back(evicted);
+    }
+
+    fn invalidate(&mut self, item: I) {
+        // the cached copy is stale, so it's dropped rather than written back
+        self.dirty.remove(&item);
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.policy);
+    }
+}
+
+/// The hit ratios of reads and writes, kept separately.
+///
+/// Accesses are writes if [`Item::is_write`] is true, like the writes in an ATF trace.
+///
+/// ```
+/// use cache_sim::{Cache, GeneralModelItem, Lru};
+/// use cache_sim::stats::ReadWriteHits;
+///
+/// let read = |uid| GeneralModelItem::new(uid, 1.0, 1);
+/// let write = |uid| read(uid).as_write();
+///
+/// let mut c = Cache::<Lru<_>, ReadWriteHits, _>::new(2);
+/// c.access(read(0)); // read miss
+/// c.access(write(0)); // write hit
+/// c.access(write(1)); // write miss
+/// c.access(read(1)); // read hit
+/// c.access(read(0)); // read hit
+///
+/// assert_eq!(c.stat().reads.ratio(), 2.0 / 3.0);
+/// assert_eq!(c.stat().writes.ratio(), 1.0 / 2.0);
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct ReadWriteHits {
+    /// The hits and total of the reads.
+    pub reads: HitRatio,
+    /// The hits and total of the writes.
+    pub writes: HitRatio,
+}
+
+impl<I: Item> Stat<I> for ReadWriteHits {
+    fn update(&mut self, set: &HashSet<I>, next: I, to_be_evicted: &HashSet<I>) {
+        if next.is_write() {
+            self.writes.update(set, next, to_be_evicted);
+        } else {
+            self.reads.update(set, next, to_be_evicted);
+        }
+    }
+}
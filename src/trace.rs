@@ -1,23 +1,66 @@
 //! A trace of accesses.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::fmt::Display;
 
+use anyhow::Context;
 use itertools::Itertools;
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize};
 
+use crate::atf::OpRecord;
 use crate::output::histogram_out;
 use crate::output::write_header;
-use crate::{condition::Condition, item::Item, stats::Stat};
+use crate::{condition::Condition, item::Item, stats::Stat, GeneralModelItem};
 
 /// A trace.
-#[derive(Debug, PartialEq, Eq, Hash, Default)]
+///
+/// Traces can be serialized, e.g. to save a preprocessed trace between runs. Deserializing checks
+/// that there's a timestamp for every access, if there are any timestamps.
+#[derive(Debug, PartialEq, Eq, Hash, Default, Serialize)]
 pub struct Trace<I: Item = u32> {
     inner: Vec<I>,
+    /// The time of each access, if the trace came with them.
+    timestamps: Option<Vec<u64>>,
+}
+
+/// A trace as it's deserialized, before checking that its timestamps line up with its accesses.
+#[derive(Deserialize)]
+struct RawTrace<I> {
+    inner: Vec<I>,
+    #[serde(default)]
+    timestamps: Option<Vec<u64>>,
+}
+
+impl<I: Item> Trace<I> {
+    /// Check that a deserialized trace is consistent.
+    fn from_raw(raw: RawTrace<I>) -> Result<Self, String> {
+        match &raw.timestamps {
+            Some(timestamps) if timestamps.len() != raw.inner.len() => Err(format!(
+                "there are {} timestamps for {} accesses",
+                timestamps.len(),
+                raw.inner.len()
+            )),
+            _ => Ok(Self {
+                inner: raw.inner,
+                timestamps: raw.timestamps,
+            }),
+        }
+    }
+}
+
+impl<'de, I: Item + Deserialize<'de>> Deserialize<'de> for Trace<I> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::from_raw(RawTrace::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
 }
 
 impl<I: Item> From<Vec<I>> for Trace<I> {
     fn from(trace: Vec<I>) -> Self {
-        Self { inner: trace }
+        Self {
+            inner: trace,
+            timestamps: None,
+        }
     }
 }
 
@@ -45,6 +88,72 @@ impl<I: Item> Trace<I> {
         freqs
     }
 
+    /// Calculate the empirical distribution of the item that follows the context, which is a
+    /// sequence of consecutive accesses.
+    ///
+    /// The probabilities sum to one, unless the context is never followed by anything, in which
+    /// case the distribution is empty. An empty context gives the distribution of all items.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    ///
+    /// let trace = Trace::from(vec![0, 1, 0, 2, 0, 1]);
+    /// let after_zero = trace.next_item_distribution(&[0]);
+    /// assert_eq!(after_zero.len(), 2);
+    /// assert!((after_zero[&1] - 2.0 / 3.0).abs() < 1e-10);
+    /// assert!((after_zero[&2] - 1.0 / 3.0).abs() < 1e-10);
+    ///
+    /// // the last access isn't followed by anything
+    /// assert!(trace.next_item_distribution(&[2, 0, 1]).is_empty());
+    /// ```
+    #[must_use]
+    pub fn next_item_distribution(&self, context: &[I]) -> HashMap<I, f64> {
+        let mut freqs: HashMap<I, u32> = HashMap::new();
+
+        for window in self.inner.windows(context.len() + 1) {
+            let (prefix, next) = window.split_at(context.len());
+            if prefix == context {
+                *freqs.entry(next[0]).or_insert(0) += 1;
+            }
+        }
+
+        let total = f64::from(freqs.values().sum::<u32>());
+        freqs
+            .into_iter()
+            .map(|(item, freq)| (item, f64::from(freq) / total))
+            .collect()
+    }
+
+    /// Calculate the mutual information, in bits, between each item and its outgoing stride,
+    /// which is the difference between the [`Item::id`]s of the next item and it.
+    ///
+    /// This is how much knowing the current item tells us about the next stride. The last access
+    /// has no outgoing stride, so it isn't counted in any of the distributions.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    ///
+    /// // the stride is always one, so the item doesn't tell us anything
+    /// let trace = Trace::from(vec![0, 1, 2, 3]);
+    /// assert!(trace.item_stride_mutual_information().abs() < 1e-10);
+    /// ```
+    #[must_use]
+    pub fn item_stride_mutual_information(&self) -> f64 {
+        let mut items: HashMap<I, u32> = HashMap::new();
+        let mut strides: HashMap<i64, u32> = HashMap::new();
+        let mut joint: HashMap<(I, i64), u32> = HashMap::new();
+
+        for window in self.inner.windows(2) {
+            let stride = i64::from(window[1].id()) - i64::from(window[0].id());
+
+            *items.entry(window[0]).or_insert(0) += 1;
+            *strides.entry(stride).or_insert(0) += 1;
+            *joint.entry((window[0], stride)).or_insert(0) += 1;
+        }
+
+        entropy(&items) + entropy(&strides) - entropy(&joint)
+    }
+
     /// Calculate the stack distances.
     ///
     /// ```
@@ -58,26 +167,129 @@ impl<I: Item> Trace<I> {
     /// ```
     ///
     /// For more details, see [`StackDistance`].
+    ///
+    /// This takes `O(n log n)` time in the length of the trace. To compute the distances one
+    /// access at a time, use a [`StackDistanceTracker`].
+    ///
+    /// # Panics
+    ///
+    /// If the total size of the items above an item on the stack doesn't fit in a `u32`.
     #[must_use]
     pub fn stack_distances(&self) -> StackDistance {
-        let mut distances = vec![Some(0); self.len()];
+        let mut tracker = StackDistanceTracker::default();
+
+        StackDistance {
+            inner: self.iter().map(|curr| tracker.push(*curr)).collect(),
+        }
+    }
 
-        let mut stack: Vec<&I> = Vec::new();
+    /// Calculate the reuse distances, which are the number of accesses since the last access to
+    /// the same item, or `None` if the item hasn't been accessed before.
+    ///
+    /// Unlike the stack distance, this counts every intervening access, including repeated
+    /// accesses to the same item.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    ///
+    /// // with no repeats in between, the two distances agree
+    /// let trace = Trace::from(vec![0, 0, 1, 0]);
+    /// assert_eq!(trace.reuse_distances(), vec![None, Some(0), None, Some(1)]);
+    /// assert_eq!(trace.stack_distances().inner(), trace.reuse_distances());
+    ///
+    /// // but 1 is only one item on the stack, while it's two accesses
+    /// let trace = Trace::from(vec![0, 1, 1, 0]);
+    /// assert_eq!(trace.reuse_distances(), vec![None, None, Some(0), Some(2)]);
+    /// assert_eq!(trace.stack_distances().inner(), &[None, None, Some(0), Some(1)]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If a reuse distance doesn't fit in a `u32`.
+    #[must_use]
+    pub fn reuse_distances(&self) -> Vec<Option<u32>> {
+        let mut last_seen: HashMap<I, usize> = HashMap::new();
+
+        self.iter()
+            .enumerate()
+            .map(|(i, curr)| {
+                last_seen.insert(*curr, i).map(|position| {
+                    u32::try_from(i - position - 1).expect("reuse distance fits in a u32")
+                })
+            })
+            .collect()
+    }
+
+    /// Calculate the histogram of inter-reference times, which are the gaps in positions between
+    /// consecutive references to the same item, across all items.
+    ///
+    /// First references have nothing before them, so they aren't counted.
+    ///
+    /// ```
+    /// use cache_sim::{trace::entropy, Trace};
+    ///
+    /// let gaps = Trace::from(vec![0, 1, 0, 0, 1, 2]).inter_reference_times();
+    /// assert_eq!(gaps.get(&1), Some(&1)); // 0 at 2 and 3
+    /// assert_eq!(gaps.get(&2), Some(&1)); // 0 at 0 and 2
+    /// assert_eq!(gaps.get(&3), Some(&1)); // 1 at 1 and 4
+    /// assert_eq!(gaps.values().sum::<u32>(), 3);
+    ///
+    /// assert!((entropy(&gaps) - 3_f64.log2()).abs() < 1e-10);
+    /// ```
+    #[must_use]
+    pub fn inter_reference_times(&self) -> HashMap<u64, u32> {
+        let mut last_seen: HashMap<I, usize> = HashMap::new();
+        let mut gaps = HashMap::new();
 
         for (i, curr) in self.iter().enumerate() {
-            if let Some(position) = stack.iter().position(|n| n == &curr) {
-                // skip position + 1, then sum all the sizes until the top of the stack
-                // this is our notion of size-aware stack distance, which generalizes the normal
-                // version from the paging model
-                distances[i] = Some(stack.iter().skip(position + 1).map(|i| i.size()).sum());
-                stack.remove(position);
-            } else {
-                distances[i] = None;
+            if let Some(position) = last_seen.insert(*curr, i) {
+                *gaps.entry((i - position) as u64).or_insert(0) += 1;
             }
-            stack.push(curr);
         }
 
-        StackDistance { inner: distances }
+        gaps
+    }
+
+    /// Calculate the working set size curve, which is, for each access, the number of distinct
+    /// items in the window of the last `tau` accesses, up to and including it.
+    ///
+    /// Until there are `tau` accesses, the window is all the accesses so far.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    ///
+    /// let trace = Trace::from(vec![0, 1, 0, 2, 2, 1]);
+    /// assert_eq!(trace.working_set_size(1), vec![1, 1, 1, 1, 1, 1]);
+    /// assert_eq!(trace.working_set_size(2), vec![1, 2, 2, 2, 1, 2]);
+    /// assert_eq!(trace.working_set_size(3), vec![1, 2, 2, 3, 2, 2]);
+    /// ```
+    #[must_use]
+    pub fn working_set_size(&self, tau: usize) -> Vec<usize> {
+        // how many times each item is in the window
+        let mut window: HashMap<I, usize> = HashMap::new();
+
+        self.iter()
+            .enumerate()
+            .map(|(i, curr)| {
+                if tau == 0 {
+                    return 0;
+                }
+
+                *window.entry(*curr).or_insert(0) += 1;
+
+                if i >= tau {
+                    // the item leaving the window was counted when it entered
+                    if let Entry::Occupied(mut count) = window.entry(self.inner[i - tau]) {
+                        *count.get_mut() -= 1;
+                        if *count.get() == 0 {
+                            count.remove();
+                        }
+                    }
+                }
+
+                window.len()
+            })
+            .collect()
     }
 
     /// Write the conditional frequencies for each condition to the output stream.
@@ -113,7 +325,120 @@ impl<I: Item> Trace<I> {
         Ok(())
     }
 
-    pub fn iter(&self) -> std::slice::Iter<I> {
+    /// Add all the accesses of `other` to the end of this trace.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    ///
+    /// let mut trace = Trace::from(vec![0, 1]);
+    /// trace.append(Trace::from(vec![1, 2]));
+    /// assert_eq!(trace.inner(), &[0, 1, 1, 2]);
+    /// ```
+    ///
+    /// The result only has timestamps if both traces do, or if one of them is empty and the other
+    /// does.
+    pub fn append(&mut self, other: Self) {
+        self.timestamps = match (self.timestamps.take(), other.timestamps) {
+            (Some(mut timestamps), Some(other)) => {
+                timestamps.extend(other);
+                Some(timestamps)
+            }
+            (None, Some(other)) if self.inner.is_empty() => Some(other),
+            (Some(timestamps), None) if other.inner.is_empty() => Some(timestamps),
+            _ => None,
+        };
+        self.inner.extend(other.inner);
+    }
+
+    /// Stitch a sequence of traces together, one after the other.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    ///
+    /// let parts = [Trace::from(vec![0, 1]), Trace::from(vec![]), Trace::from(vec![2])];
+    /// let trace = Trace::concat(parts);
+    /// assert_eq!(trace.inner(), &[0, 1, 2]);
+    /// ```
+    pub fn concat(traces: impl IntoIterator<Item = Self>) -> Self {
+        let mut concatenated = Self::default();
+        for trace in traces {
+            concatenated.append(trace);
+        }
+        concatenated
+    }
+
+    /// Get a new trace of only the accesses for which `keep` is true, along with their
+    /// timestamps.
+    fn select(&self, mut keep: impl FnMut(usize, &I) -> bool) -> Self {
+        let indices = (0..self.len())
+            .filter(|&i| keep(i, &self.inner[i]))
+            .collect::<Vec<_>>();
+
+        Self {
+            inner: indices.iter().map(|&i| self.inner[i]).collect(),
+            timestamps: self
+                .timestamps
+                .as_ref()
+                .map(|timestamps| indices.iter().map(|&i| timestamps[i]).collect()),
+        }
+    }
+
+    /// Get a new trace of only the accesses which satisfy the predicate.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    ///
+    /// let evens = Trace::from(vec![0, 1, 2, 3, 0]).filter(|i| i % 2 == 0);
+    /// assert_eq!(evens.inner(), &[0, 2, 0]);
+    /// ```
+    #[must_use]
+    pub fn filter(&self, predicate: impl Fn(&I) -> bool) -> Self {
+        self.select(|_, i| predicate(i))
+    }
+
+    /// Get a new trace of every `factor`th access, starting with the first.
+    ///
+    /// Subsampling changes the locality of the trace, so the stack distances of the subsampled
+    /// trace aren't those of the original.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    ///
+    /// let trace = Trace::from(vec![0, 1, 2, 3, 4]).subsample(2);
+    /// assert_eq!(trace.inner(), &[0, 2, 4]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `factor` is zero.
+    #[must_use]
+    pub fn subsample(&self, factor: usize) -> Self {
+        assert!(factor > 0, "The subsampling factor must be positive.");
+        self.select(|i, _| i % factor == 0)
+    }
+
+    /// Get a new trace where each access is kept independently with probability `frac`.
+    ///
+    /// Like [`Self::subsample`], this doesn't preserve stack distances.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let trace = Trace::from(vec![0, 1, 2, 3, 4]);
+    /// assert_eq!(trace.sample_fraction(1.0, &mut StdRng::seed_from_u64(0)), trace);
+    /// assert!(trace.sample_fraction(0.0, &mut StdRng::seed_from_u64(0)).is_empty());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `frac` isn't between zero and one.
+    #[must_use]
+    pub fn sample_fraction(&self, frac: f64, rng: &mut impl Rng) -> Self {
+        self.select(|_, _| rng.gen_bool(frac))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, I> {
         self.inner.iter()
     }
 
@@ -132,6 +457,21 @@ impl<I: Item> Trace<I> {
         self.inner
     }
 
+    /// Get the time of each access, if the trace has them.
+    ///
+    /// Traces parsed from `atf` files with [`Trace::from_records`] have timestamps, while those
+    /// built from plain vectors of items don't.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    ///
+    /// assert_eq!(Trace::from(vec![0, 1]).timestamps(), None);
+    /// ```
+    #[must_use]
+    pub fn timestamps(&self) -> Option<&[u64]> {
+        self.timestamps.as_deref()
+    }
+
     /// Get the length of the trace.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -169,6 +509,7 @@ impl<I: Item> FromIterator<I> for Trace<I> {
     fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
         Self {
             inner: Vec::from_iter(iter),
+            timestamps: None,
         }
     }
 }
@@ -186,7 +527,115 @@ where
     }
 }
 
+impl Trace<GeneralModelItem> {
+    /// Build a trace out of the records of an `atf` file, keeping the timestamp of each access.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), csv::Error> {
+    /// use cache_sim::{atf::parse, Trace};
+    ///
+    /// let trace = Trace::from_records(parse(b"0,5,R,1,1\n1,8,W,1,1".as_slice())?);
+    /// assert_eq!(trace.len(), 2);
+    /// assert_eq!(trace.timestamps(), Some([5, 8].as_slice()));
+    /// # Ok(())}
+    /// ```
+    #[must_use]
+    pub fn from_records(records: Vec<OpRecord>) -> Self {
+        Self {
+            timestamps: Some(records.iter().map(OpRecord::timestamp).collect()),
+            inner: records.into_iter().map(GeneralModelItem::from).collect(),
+        }
+    }
+
+    /// Read a trace out of the `atf` file at `path`, keeping its timestamps.
+    ///
+    /// ```no_run
+    /// use cache_sim::{GeneralModelItem, Trace};
+    ///
+    /// let trace = Trace::<GeneralModelItem>::from_atf_path("traces/ycsb-sample.atf")?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// If the file can't be opened, or it isn't a valid `atf` file.
+    pub fn from_atf_path(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("couldn't open {}", path.display()))?;
+        let records = crate::atf::parse(std::io::BufReader::new(file))
+            .with_context(|| format!("couldn't parse {}", path.display()))?;
+
+        Ok(Self::from_records(records))
+    }
+
+    /// Build a trace out of the id, cost, and size of each access.
+    ///
+    /// Items are identified only by their ids, so accesses with the same id are to the same item.
+    ///
+    /// ```
+    /// use cache_sim::{NoCondition, Trace};
+    ///
+    /// let trace = Trace::from_tuples([(0, 1.0, 2), (1, 5.0, 1), (0, 1.0, 2), (2, 1.0, 1)])?;
+    /// assert_eq!(trace.len(), 4);
+    /// assert_eq!(trace.frequency_histogram(&NoCondition).len(), 3);
+    /// assert_eq!(trace[0], trace[2]);
+    ///
+    /// // items only store 32-bit ids
+    /// assert!(Trace::from_tuples([(u64::from(u32::MAX) + 1, 1.0, 1)]).is_err());
+    /// # Ok::<(), std::num::TryFromIntError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// If an id doesn't fit in a `u32`.
+    pub fn from_tuples(
+        iter: impl IntoIterator<Item = (u64, f64, u32)>,
+    ) -> Result<Self, std::num::TryFromIntError> {
+        iter.into_iter()
+            .map(|(id, cost, size)| Ok(GeneralModelItem::new(u32::try_from(id)?, cost, size)))
+            .collect()
+    }
+}
+
 impl Trace<u32> {
+    /// Generate a synthetic trace of `length` accesses to the items `0..num_items`, where the
+    /// probability of accessing item `k` is proportional to `1 / (k + 1)^exponent`.
+    ///
+    /// Higher exponents give more skewed traces, and an exponent of zero is uniform.
+    ///
+    /// ```
+    /// use cache_sim::Trace;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let trace = Trace::zipf(100, 1000, 1.0, &mut StdRng::seed_from_u64(0));
+    /// assert_eq!(trace.len(), 1000);
+    /// assert!(trace.iter().all(|&i| i < 100));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `num_items` is zero or doesn't fit in a `u32`.
+    pub fn zipf(num_items: u64, length: usize, exponent: f64, rng: &mut impl Rng) -> Self {
+        assert!(num_items > 0, "There must be at least one item.");
+        let num_items = u32::try_from(num_items).expect("item ids fit in a u32");
+
+        // the unnormalized cumulative distribution, so sampling is a binary search
+        let cdf = (1..=num_items)
+            .scan(0.0, |total, k| {
+                *total += f64::from(k).powf(-exponent);
+                Some(*total)
+            })
+            .collect::<Vec<_>>();
+        let total = cdf[cdf.len() - 1];
+
+        (0..length)
+            .map(|_| {
+                let target = rng.gen::<f64>() * total;
+                let index = cdf.partition_point(|&p| p <= target).min(cdf.len() - 1);
+                u32::try_from(index).expect("index is less than num_items")
+            })
+            .collect()
+    }
+
     /// If the elements in the trace are all smaller than 26, display them as letters instead.
     ///
     /// ```
@@ -272,23 +721,49 @@ impl StackDistance {
     /// assert_eq!(distance_hist, vec![1, 2, 1]);
     /// assert_eq!(infinities, 3);
     /// ```
+    #[must_use]
     pub fn histogram(&self) -> (Vec<usize>, usize) {
-        let max = self.inner.iter().flatten().max();
-
-        let mut freqs = max.map_or_else(Vec::new, |max| vec![0; *max as usize + 1]);
-
-        let mut infinities = 0;
+        distance_histogram(&self.inner)
+    }
 
-        for &i in &self.inner {
-            #[allow(clippy::option_if_let_else)]
-            if let Some(i) = i {
-                freqs[i as usize] += 1;
-            } else {
-                infinities += 1;
-            }
-        }
+    /// Calculate the miss ratio curve, which is the fraction of accesses that miss in an LRU cache
+    /// of each size from zero up to the largest stack distance.
+    ///
+    /// An access misses if its stack distance is at least the size of the cache. In larger caches,
+    /// only the first accesses to each item miss.
+    ///
+    /// This only makes sense in the paging model, where every item has size one; with sizes, an
+    /// item being within the stack distance doesn't mean that it fits.
+    ///
+    /// ```
+    /// use cache_sim::{Cache, Lru, Trace};
+    /// use cache_sim::stats::MissCount;
+    ///
+    /// let trace = Trace::from(vec![0, 0, 1, 0, 3, 0, 1]);
+    /// let curve = trace.stack_distances().miss_ratio_curve();
+    /// assert_eq!(curve, vec![1.0, 6.0 / 7.0, 4.0 / 7.0]);
+    ///
+    /// for size in [1, 2] {
+    ///     let mut c = Cache::<Lru, MissCount>::new(size);
+    ///     c.run_trace(&trace);
+    ///     assert_eq!(f64::from(c.stat().0) / 7.0, curve[size as usize]);
+    /// }
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // traces are much shorter than 2^52
+    pub fn miss_ratio_curve(&self) -> Vec<f64> {
+        let (freqs, _) = self.histogram();
+        let total = self.inner.len() as f64;
 
-        (freqs, infinities)
+        // a cache of size n has a hit for every distance less than n
+        freqs
+            .iter()
+            .scan(0, |hits, &freq| {
+                let misses = self.inner.len() - *hits;
+                *hits += freq;
+                Some(misses as f64 / total)
+            })
+            .collect()
     }
 
     /// Get a reference to the inner vector of distances.
@@ -309,46 +784,308 @@ impl StackDistance {
     }
 }
 
-/// Returns the entropy of a given distribution.
-#[must_use]
-pub fn entropy<I: Item, H: std::hash::BuildHasher>(histogram: &HashMap<I, u32, H>) -> f64 {
-    let total = f64::from(histogram.values().sum::<u32>());
-    -histogram
-        .values()
-        .map(|&i| (f64::from(i) / total) * ((f64::from(i) / total).log2()))
-        .sum::<f64>()
+/// Computes the stack distance of each access as it's pushed, for when the accesses aren't all
+/// known up front.
+///
+/// This gives the same distances as [`Trace::stack_distances`], and each push takes `O(log n)`
+/// time. It remembers one size for every access pushed so far, so its memory grows with the
+/// length of the trace.
+///
+/// ```
+/// use cache_sim::trace::StackDistanceTracker;
+///
+/// let mut tracker = StackDistanceTracker::default();
+/// assert_eq!(tracker.push(0), None);
+/// assert_eq!(tracker.push(1), None);
+/// assert_eq!(tracker.push(1), Some(0));
+/// assert_eq!(tracker.push(0), Some(1));
+/// ```
+#[derive(Default, Debug)]
+pub struct StackDistanceTracker<I: Item = u32> {
+    /// Each position holds the size of the item accessed there, if that's the most recent access
+    /// to the item, and zero otherwise; so the items above an item on the stack are exactly the
+    /// nonzero positions after its most recent access.
+    sizes: Fenwick,
+    last_seen: HashMap<I, usize>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl<I: Item> StackDistanceTracker<I> {
+    /// Push an access to `item` onto the stack, returning its stack distance, or `None` if it
+    /// hasn't been accessed before.
+    ///
+    /// # Panics
+    ///
+    /// If the total size of the items above `item` on the stack doesn't fit in a `u32`.
+    pub fn push(&mut self, item: I) -> Option<u32> {
+        let i = self.sizes.len();
 
-    mod stack_distance {
-        use super::*;
+        let distance = self.last_seen.insert(item, i).map(|position| {
+            // sum all the sizes until the top of the stack
+            // this is our notion of size-aware stack distance, which generalizes the normal
+            // version from the paging model
+            let distance = self.sizes.prefix_sum(i) - self.sizes.prefix_sum(position + 1);
+            self.sizes.set(position, 0);
+            u32::try_from(distance).expect("stack distance fits in a u32")
+        });
+        self.sizes.push(item.size());
 
-        macro_rules! stack_distance_test {
-            ($name:ident: $($in:expr),* => $($out:expr),*) => {
-                #[test]
-                fn $name() {
-                    assert_eq!(Trace::from(vec![$($in),*]).stack_distances().inner(), vec![$($out),*])
-                }
-            };
+        distance
+    }
+}
+
+/// Displays the distances in order, separated by commas, with `∞` for infinities.
+///
+/// ```
+/// use cache_sim::Trace;
+///
+/// let distances = Trace::from(vec![0, 0, 1, 0, 3, 0, 1]).stack_distances();
+/// assert_eq!(distances.to_string(), "∞, 0, ∞, 1, ∞, 1, 2");
+/// ```
+impl Display for StackDistance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, distance) in self.inner.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            match distance {
+                Some(distance) => write!(f, "{distance}")?,
+                None => write!(f, "∞")?,
+            }
         }
 
-        stack_distance_test!(basic: 1, 2, 3 => None, None, None);
-        stack_distance_test!(repeated: 1, 1, 1 => None, Some(0), Some(0));
-        stack_distance_test!(one_two: 1, 2, 1, 1, 1 => None, None, Some(1), Some(0), Some(0));
-        stack_distance_test!(one_repeated: 1, 2, 3, 1 => None, None, None, Some(2));
-        // stack_distance_test!(empty: => );
+        Ok(())
+    }
+}
 
-        #[test]
-        fn with_sizes() {
-            use crate::item::GeneralModelGenerator;
+/// Read a trace out of an arbitrary csv, where each row is an access, using the given columns as
+/// the id, size, and cost of each item. The first column has index zero.
+///
+/// Without a size column, every item has size one, and without a cost column, every item costs
+/// one. Like `atf` files, there's no header row, and rows beginning with a `#` are skipped.
+///
+/// ```
+/// # fn main() -> Result<(), csv::Error> {
+/// use cache_sim::item::Item;
+/// use cache_sim::trace::from_csv;
+///
+/// let trace = from_csv(b"# address, bytes\n7,4096\n8,512".as_slice(), 0, Some(1), None)?;
+/// assert_eq!(trace[0].id(), 7);
+/// assert_eq!(trace[1].size(), 512);
+/// assert_eq!(trace[1].cost(), 1.0);
+/// # Ok(())}
+/// ```
+///
+/// # Errors
+/// If the csv can't be read, or if a row is missing one of the columns or its value in that
+/// column isn't a number.
+pub fn from_csv<R: std::io::Read>(
+    input: R,
+    id_col: usize,
+    size_col: Option<usize>,
+    cost_col: Option<usize>,
+) -> Result<Trace<GeneralModelItem>, csv::Error> {
+    /// Parse the value in a column, with an error to say where it went wrong if it can't be.
+    fn field<T: std::str::FromStr>(
+        row: &csv::StringRecord,
+        column: usize,
+    ) -> Result<T, csv::Error> {
+        let line = row.position().map_or(0, csv::Position::line);
+        let invalid = |message: String| {
+            csv::Error::from(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("line {line}: {message}"),
+            ))
+        };
 
-            let mut g = GeneralModelGenerator::new();
+        let value = row
+            .get(column)
+            .ok_or_else(|| invalid(format!("there is no column {column}")))?;
+        value
+            .trim()
+            .parse()
+            .map_err(|_| invalid(format!("{value:?} in column {column} isn't a number")))
+    }
 
-            let a = g.item(1.0, 2);
-            let b = g.item(1.0, 4);
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .comment(Some(b'#'))
+        .flexible(true)
+        .from_reader(input)
+        .records()
+        .map(|row| {
+            let row = row?;
+            Ok(GeneralModelItem::new(
+                field(&row, id_col)?,
+                cost_col.map_or(Ok(1.0), |column| field(&row, column))?,
+                size_col.map_or(Ok(1), |column| field(&row, column))?,
+            ))
+        })
+        .collect()
+}
+
+/// A Fenwick tree of sizes, which supports adding a position, setting the size at a position, and
+/// summing the sizes before a position, all in `O(log n)` time.
+///
+/// The sums are over the whole prefix, not just the part that's on the stack, so they're kept
+/// wide enough that they don't overflow even when the stack distances would fit in a `u32`.
+#[derive(Debug)]
+struct Fenwick {
+    /// The tree itself, which is one-indexed, so `tree[0]` is unused.
+    tree: Vec<u64>,
+    /// The size at each position, so we can find the difference when it's set.
+    values: Vec<u32>,
+}
+
+impl Default for Fenwick {
+    fn default() -> Self {
+        Self {
+            tree: vec![0],
+            values: Vec::new(),
+        }
+    }
+}
+
+impl Fenwick {
+    /// The number of positions in the tree.
+    const fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Add a position to the end of the tree, holding `value`.
+    fn push(&mut self, value: u32) {
+        // the new node covers the positions from just after its parent up to itself
+        let i = self.tree.len();
+        let node =
+            u64::from(value) + self.prefix_sum(i - 1) - self.prefix_sum(i - (i & i.wrapping_neg()));
+
+        self.tree.push(node);
+        self.values.push(value);
+    }
+
+    /// Set the size at `position`.
+    fn set(&mut self, position: usize, value: u32) {
+        let old = std::mem::replace(&mut self.values[position], value);
+
+        let mut i = position + 1;
+        while i < self.tree.len() {
+            self.tree[i] = self.tree[i] + u64::from(value) - u64::from(old);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Sum the sizes of the positions before `end`.
+    fn prefix_sum(&self, end: usize) -> u64 {
+        let mut sum = 0;
+
+        let mut i = end;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+
+        sum
+    }
+}
+
+/// Calculate the histogram of a sequence of stack distances, plus the count of infinities.
+pub(crate) fn distance_histogram(distances: &[Option<u32>]) -> (Vec<usize>, usize) {
+    let max = distances.iter().flatten().max();
+
+    let mut freqs = max.map_or_else(Vec::new, |max| vec![0; *max as usize + 1]);
+
+    let mut infinities = 0;
+
+    for &i in distances {
+        #[allow(clippy::option_if_let_else)]
+        if let Some(i) = i {
+            freqs[i as usize] += 1;
+        } else {
+            infinities += 1;
+        }
+    }
+
+    (freqs, infinities)
+}
+
+/// Returns the entropy of a given distribution, in bits.
+///
+/// The distribution can be over anything, like items or the gaps between references to them.
+#[must_use]
+pub fn entropy<K, H: std::hash::BuildHasher>(histogram: &HashMap<K, u32, H>) -> f64 {
+    entropy_base(histogram, 2.0)
+}
+
+/// Returns the entropy of a given distribution, with logarithms in the given base; e.g. base two
+/// gives bits, and base `e` gives nats.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// use cache_sim::trace::entropy_base;
+///
+/// let uniform = HashMap::from([(0, 1), (1, 1), (2, 1), (3, 1)]);
+/// assert!((entropy_base(&uniform, 2.0) - 2.0).abs() < 1e-10);
+/// assert!((entropy_base(&uniform, 4.0) - 1.0).abs() < 1e-10);
+/// ```
+///
+/// # Panics
+///
+/// If the base isn't positive, or is one, since there are no logarithms in those bases.
+#[must_use]
+pub fn entropy_base<K, H: std::hash::BuildHasher>(
+    histogram: &HashMap<K, u32, H>,
+    base: f64,
+) -> f64 {
+    assert!(
+        base > 0.0 && (base - 1.0).abs() > f64::EPSILON,
+        "The base of the logarithm must be positive and not one."
+    );
+
+    // symbols which never occur don't contribute anything, but `0 * log(0)` is NaN
+    let counts = histogram.values().filter(|&&i| i > 0).collect::<Vec<_>>();
+
+    // with at most one symbol there's nothing uncertain; this also avoids dividing by a total of
+    // zero
+    if counts.len() <= 1 {
+        return 0.0;
+    }
+
+    let total = f64::from(counts.iter().copied().sum::<u32>());
+    -counts
+        .into_iter()
+        .map(|&i| (f64::from(i) / total) * ((f64::from(i) / total).log(base)))
+        .sum::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod stack_distance {
+        use super::*;
+
+        macro_rules! stack_distance_test {
+            ($name:ident: $($in:expr),* => $($out:expr),*) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Trace::from(vec![$($in),*]).stack_distances().inner(), vec![$($out),*])
+                }
+            };
+        }
+
+        stack_distance_test!(basic: 1, 2, 3 => None, None, None);
+        stack_distance_test!(repeated: 1, 1, 1 => None, Some(0), Some(0));
+        stack_distance_test!(one_two: 1, 2, 1, 1, 1 => None, None, Some(1), Some(0), Some(0));
+        stack_distance_test!(one_repeated: 1, 2, 3, 1 => None, None, None, Some(2));
+        // stack_distance_test!(empty: => );
+
+        #[test]
+        fn with_sizes() {
+            use crate::item::GeneralModelGenerator;
+
+            let mut g = GeneralModelGenerator::new();
+
+            let a = g.item(1.0, 2);
+            let b = g.item(1.0, 4);
             let c = g.item(1.0, 3);
 
             let trace = Trace::from(vec![a, b, c, a]);
@@ -357,6 +1094,526 @@ mod tests {
                 vec![None, None, None, Some(7)]
             );
         }
+
+        #[test]
+        fn large_sizes() {
+            use crate::item::GeneralModelItem;
+
+            // the distance is over a billion, and the sum of every size in the trace doesn't fit
+            // in a u32, but the distance itself does
+            let big = |uid| GeneralModelItem::new(uid, 1.0, 1_500_000_000);
+            let small = GeneralModelItem::new(9, 1.0, 1);
+
+            let trace = Trace::from(vec![big(0), big(1), big(2), small, big(2), small]);
+            assert_eq!(
+                trace.stack_distances().inner(),
+                &[None, None, None, None, Some(1), Some(1_500_000_000)]
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "fits in a u32")]
+        fn too_large_sizes() {
+            use crate::item::GeneralModelItem;
+
+            let big = |uid| GeneralModelItem::new(uid, 1.0, 3_000_000_000);
+            let _ = Trace::from(vec![big(0), big(1), big(2), big(0)]).stack_distances();
+        }
+
+        #[test]
+        fn tracker_matches_offline() {
+            let trace = Trace::from(vec![0, 0, 1, 0, 3, 0, 1]);
+
+            let mut tracker = StackDistanceTracker::default();
+            let online = trace.iter().map(|i| tracker.push(*i)).collect::<Vec<_>>();
+
+            assert_eq!(online, trace.stack_distances().inner());
+        }
+
+        /// The original `O(n * d)` implementation, which scans the stack on every access.
+        fn naive_stack_distances<I: Item>(trace: &Trace<I>) -> Vec<Option<u32>> {
+            let mut distances = Vec::new();
+            let mut stack: Vec<&I> = Vec::new();
+
+            for curr in trace {
+                if let Some(position) = stack.iter().position(|n| n == &curr) {
+                    distances.push(Some(
+                        stack.iter().skip(position + 1).map(|i| i.size()).sum(),
+                    ));
+                    stack.remove(position);
+                } else {
+                    distances.push(None);
+                }
+                stack.push(curr);
+            }
+
+            distances
+        }
+
+        #[test]
+        fn matches_naive_on_large_trace() {
+            use rand::rngs::StdRng;
+            use rand::{Rng, SeedableRng};
+
+            let mut rng = StdRng::seed_from_u64(0);
+            let trace = (0..100_000)
+                .map(|_| rng.gen_range(0..500))
+                .collect::<Trace>();
+
+            assert_eq!(
+                trace.stack_distances().inner(),
+                naive_stack_distances(&trace)
+            );
+        }
+
+        #[test]
+        fn matches_naive_with_sizes() {
+            use crate::item::GeneralModelItem;
+            use rand::rngs::StdRng;
+            use rand::{Rng, SeedableRng};
+
+            let mut rng = StdRng::seed_from_u64(0);
+            let trace = (0..10_000)
+                .map(|_| {
+                    let uid = rng.gen_range(0..100);
+                    GeneralModelItem::new(uid, 1.0, uid % 7 + 1)
+                })
+                .collect::<Trace<_>>();
+
+            assert_eq!(
+                trace.stack_distances().inner(),
+                naive_stack_distances(&trace)
+            );
+        }
+    }
+
+    mod reuse_distance {
+        use super::*;
+
+        macro_rules! reuse_distance_test {
+            ($name:ident: $($in:expr),* => $($out:expr),*) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Trace::from(vec![$($in),*]).reuse_distances(), vec![$($out),*])
+                }
+            };
+        }
+
+        reuse_distance_test!(basic: 1, 2, 3 => None, None, None);
+        reuse_distance_test!(repeated: 1, 1, 1 => None, Some(0), Some(0));
+        reuse_distance_test!(one_two: 1, 2, 2, 2, 1 => None, None, Some(0), Some(0), Some(3));
+        reuse_distance_test!(interleaved: 1, 2, 1, 2 => None, None, Some(1), Some(1));
+    }
+
+    mod inter_reference_times {
+        use super::*;
+
+        macro_rules! inter_reference_test {
+            ($name:ident: $($in:expr),* => $($out:expr),*) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Trace::from(vec![$($in),*]).inter_reference_times(), HashMap::from([$($out),*]))
+                }
+            };
+        }
+
+        inter_reference_test!(basic: 1, 2, 3 => );
+        inter_reference_test!(repeated: 1, 1, 1 => (1, 2));
+        inter_reference_test!(interleaved: 1, 2, 1, 2, 1 => (2, 3));
+        inter_reference_test!(one_repeated: 1, 2, 3, 1, 1 => (3, 1), (1, 1));
+    }
+
+    mod concat {
+        use super::*;
+
+        #[test]
+        fn stack_distances_cross_the_join() {
+            let mut trace = Trace::from(vec![0, 1]);
+            trace.append(Trace::from(vec![2, 0]));
+
+            assert_eq!(
+                trace.stack_distances().inner(),
+                Trace::from(vec![0, 1, 2, 0]).stack_distances().inner()
+            );
+        }
+
+        #[test]
+        fn concat_matches_append() {
+            let parts = || {
+                [
+                    Trace::from(vec![0, 1]),
+                    Trace::from(vec![1]),
+                    Trace::from(vec![2, 0]),
+                ]
+            };
+
+            let mut appended = Trace::default();
+            for part in parts() {
+                appended.append(part);
+            }
+
+            assert_eq!(Trace::concat(parts()), appended);
+            assert_eq!(appended.inner(), &[0, 1, 1, 2, 0]);
+        }
+    }
+
+    mod filter {
+        use super::*;
+
+        use crate::item::GeneralModelItem;
+
+        #[test]
+        fn by_size() {
+            let small = GeneralModelItem::new(0, 1.0, 1);
+            let large = GeneralModelItem::new(1, 1.0, 5);
+            let medium = GeneralModelItem::new(2, 1.0, 3);
+
+            let trace = Trace::from(vec![large, small, medium, small, large]);
+            let filtered = trace.filter(|i| i.size() >= 3);
+
+            assert_eq!(filtered.inner(), &[large, medium, large]);
+            // the small items no longer sit between the two accesses to the large one
+            assert_eq!(filtered.stack_distances().inner(), &[None, None, Some(3)]);
+        }
+    }
+
+    mod zipf {
+        use super::*;
+
+        use crate::condition::NoCondition;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        #[test]
+        fn high_exponent_is_skewed() {
+            let trace = Trace::zipf(1000, 10_000, 3.0, &mut StdRng::seed_from_u64(0));
+            let frequencies = trace.frequency_histogram(&NoCondition);
+
+            // the top item has probability 1 / zeta(3), which is about 0.83
+            let top = frequencies[&0];
+            assert!(top > 8000, "top item was only accessed {top} times");
+            assert!(frequencies.values().all(|&f| f <= top));
+        }
+
+        #[test]
+        fn zero_exponent_is_uniform() {
+            let trace = Trace::zipf(4, 10_000, 0.0, &mut StdRng::seed_from_u64(0));
+            let frequencies = trace.frequency_histogram(&NoCondition);
+
+            assert_eq!(frequencies.len(), 4);
+            assert!(frequencies.values().all(|&f| (2250..2750).contains(&f)));
+        }
+
+        #[test]
+        fn seeded_is_reproducible() {
+            assert_eq!(
+                Trace::zipf(100, 100, 1.0, &mut StdRng::seed_from_u64(1)),
+                Trace::zipf(100, 100, 1.0, &mut StdRng::seed_from_u64(1))
+            );
+        }
+    }
+
+    mod subsample {
+        use super::*;
+
+        use rand::{rngs::StdRng, SeedableRng};
+
+        #[test]
+        fn factor_two() {
+            let trace = Trace::from(vec![0, 1, 0, 2, 1, 1]);
+            let subsampled = trace.subsample(2);
+
+            assert_eq!(subsampled.len(), 3);
+            assert_eq!(subsampled.inner(), &[0, 0, 1]);
+            assert_eq!(subsampled.stack_distances().inner(), &[None, Some(0), None]);
+        }
+
+        #[test]
+        fn factor_one_is_identity() {
+            let trace = Trace::from(vec![0, 1, 0, 2]);
+            assert_eq!(trace.subsample(1), trace);
+        }
+
+        #[test]
+        fn fraction_keeps_about_that_many() {
+            let trace = (0..10_000).collect::<Trace>();
+            let sampled = trace.sample_fraction(0.25, &mut StdRng::seed_from_u64(0));
+
+            assert!((2250..2750).contains(&sampled.len()));
+            // the order of the kept accesses is preserved
+            assert!(sampled.inner().windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+
+    mod working_set_size {
+        use super::*;
+
+        macro_rules! working_set_test {
+            ($name:ident: $($in:expr),*; $tau:expr => $($out:expr),*) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(Trace::from(vec![$($in),*]).working_set_size($tau), vec![$($out),*])
+                }
+            };
+        }
+
+        working_set_test!(zero_window: 0, 1, 2; 0 => 0, 0, 0);
+        working_set_test!(window_longer_than_trace: 0, 1, 0; 5 => 1, 2, 2);
+        working_set_test!(distinct: 0, 1, 2, 3; 2 => 1, 2, 2, 2);
+        working_set_test!(repeated: 0, 0, 0, 1; 3 => 1, 1, 1, 2);
+    }
+
+    mod miss_ratio_curve {
+        use super::*;
+
+        #[test]
+        fn only_infinities() {
+            assert!(Trace::from(vec![0, 1, 2])
+                .stack_distances()
+                .miss_ratio_curve()
+                .is_empty());
+        }
+
+        #[test]
+        fn matches_lru() {
+            use crate::stats::MissCount;
+            use crate::{Cache, Lru};
+            use rand::rngs::StdRng;
+            use rand::SeedableRng;
+
+            let trace = Trace::zipf(50, 1000, 0.8, &mut StdRng::seed_from_u64(0));
+            let curve = trace.stack_distances().miss_ratio_curve();
+
+            // caches can't be empty, but everything misses in them anyway
+            assert!((curve[0] - 1.0).abs() < 1e-10);
+            for (size, ratio) in curve.into_iter().enumerate().skip(1) {
+                let mut c = Cache::<Lru, MissCount>::new(u32::try_from(size).unwrap());
+                c.run_trace(&trace);
+                assert!((f64::from(c.stat().0) / 1000.0 - ratio).abs() < 1e-10);
+            }
+        }
+    }
+
+    mod next_item_distribution {
+        use super::*;
+
+        #[test]
+        fn deterministic_pattern() {
+            let trace = Trace::from(vec![0, 1, 2, 3, 0, 1, 2, 0, 1, 2, 4]);
+            assert_eq!(
+                trace.next_item_distribution(&[0, 1]),
+                HashMap::from([(2, 1.0)])
+            );
+        }
+
+        #[test]
+        fn empty_context_is_all_items() {
+            let trace = Trace::from(vec![0, 1, 1, 1]);
+            assert_eq!(
+                trace.next_item_distribution(&[]),
+                HashMap::from([(0, 0.25), (1, 0.75)])
+            );
+        }
+
+        #[test]
+        fn context_longer_than_trace() {
+            let trace = Trace::from(vec![0, 1]);
+            assert!(trace.next_item_distribution(&[0, 1, 2]).is_empty());
+        }
+    }
+
+    mod mutual_information {
+        use super::*;
+
+        #[test]
+        fn stride_determined_by_item() {
+            // every item has its own stride: 0 -> 1, 1 -> 2, 3 -> -3
+            let trace = Trace::from(vec![0, 1, 3, 0, 1, 3, 0]);
+
+            // the last access has no stride, so it's dropped from the item distribution too
+            let items = Trace::from(trace[..trace.len() - 1].to_vec())
+                .frequency_histogram(&crate::condition::NoCondition);
+
+            assert!((trace.item_stride_mutual_information() - entropy(&items)).abs() < 1e-10);
+            assert!((entropy(&items) - 3_f64.log2()).abs() < 1e-10);
+        }
+
+        #[test]
+        fn stride_not_determined_by_item() {
+            // 0 is followed by both 1 and 2, so its stride is a fair coin, which leaves half a bit
+            // of the two bits of stride entropy unexplained
+            let trace = Trace::from(vec![0, 1, 0, 2, 0]);
+            assert!((trace.item_stride_mutual_information() - 1.5).abs() < 1e-10);
+        }
+    }
+
+    mod timestamps {
+        use super::*;
+
+        use crate::atf::parse;
+
+        const DATA: &[u8] = b"# this is my cool header!
+1,2,R,4,7,6
+0,16,W,3,4,2.5
+1,4,R,3,2,1.2";
+
+        #[test]
+        fn from_atf_path() -> anyhow::Result<()> {
+            let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/traces/ycsb-sample.atf");
+            let trace = Trace::<GeneralModelItem>::from_atf_path(path)?;
+
+            assert!(!trace.is_empty());
+            assert_eq!(trace.timestamps().map(<[_]>::len), Some(trace.len()));
+
+            assert!(Trace::<GeneralModelItem>::from_atf_path("not/a/trace.atf").is_err());
+
+            Ok(())
+        }
+
+        #[test]
+        fn survive_from_atf() -> Result<(), csv::Error> {
+            let trace = Trace::from_records(parse(DATA)?);
+            assert_eq!(trace.timestamps(), Some([2, 16, 4].as_slice()));
+
+            Ok(())
+        }
+
+        #[test]
+        fn follow_their_accesses() -> Result<(), csv::Error> {
+            let trace = Trace::from_records(parse(DATA)?);
+
+            assert_eq!(
+                trace.filter(|i| i.size() == 3).timestamps(),
+                Some([16, 4].as_slice())
+            );
+            assert_eq!(trace.subsample(2).timestamps(), Some([2, 4].as_slice()));
+
+            Ok(())
+        }
+
+        #[test]
+        fn append() -> Result<(), csv::Error> {
+            let mut trace = Trace::from_records(parse(DATA)?);
+            trace.append(Trace::from_records(parse(DATA)?));
+            assert_eq!(trace.timestamps(), Some([2, 16, 4, 2, 16, 4].as_slice()));
+
+            // an empty trace doesn't need timestamps
+            trace.append(Trace::default());
+            assert_eq!(trace.len(), 6);
+            assert!(trace.timestamps().is_some());
+
+            // but a trace without them means the result doesn't have them either
+            trace.append(Trace::from(vec![GeneralModelItem::default()]));
+            assert_eq!(trace.timestamps(), None);
+
+            Ok(())
+        }
+    }
+
+    mod serde {
+        use super::*;
+        use ::serde::de::value::{Error, MapDeserializer};
+
+        #[test]
+        fn items_round_trip() -> Result<(), csv::Error> {
+            let trace = Trace::from(vec![
+                GeneralModelItem::new(0, 1.5, 2),
+                GeneralModelItem::new(1, 4.0, 1).as_write(),
+            ]);
+
+            let mut written = Vec::new();
+            {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(&mut written);
+                for item in &trace {
+                    writer.serialize(item)?;
+                }
+                writer.flush()?;
+            }
+
+            let read = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(written.as_slice())
+                .deserialize()
+                .collect::<Result<Trace<GeneralModelItem>, _>>()?;
+
+            assert_eq!(read, trace);
+            assert!(read[1].is_write());
+            assert_eq!(read[0].cost(), 1.5);
+
+            Ok(())
+        }
+
+        #[test]
+        fn timestamps_are_optional() -> Result<(), Error> {
+            let fields = [("inner", vec![0_u32, 1, 0])];
+            let trace =
+                Trace::<u32>::deserialize(MapDeserializer::<_, Error>::new(fields.into_iter()))?;
+
+            assert_eq!(trace, Trace::from(vec![0, 1, 0]));
+            assert_eq!(trace.timestamps(), None);
+
+            Ok(())
+        }
+
+        #[test]
+        fn inconsistent_timestamps() {
+            let raw = RawTrace {
+                inner: vec![0, 1, 0],
+                timestamps: Some(vec![3, 5]),
+            };
+
+            assert_eq!(
+                Trace::from_raw(raw),
+                Err(String::from("there are 2 timestamps for 3 accesses"))
+            );
+        }
+    }
+
+    mod from_csv {
+        use super::*;
+
+        #[test]
+        fn id_only() -> Result<(), csv::Error> {
+            let trace = from_csv(b"3\n1\n3".as_slice(), 0, None, None)?;
+
+            assert_eq!(
+                trace.iter().map(Item::id).collect::<Vec<_>>(),
+                vec![3, 1, 3]
+            );
+            assert!(trace
+                .iter()
+                .all(|i| i.size() == 1 && (i.cost() - 1.0).abs() < 1e-10));
+
+            Ok(())
+        }
+
+        #[test]
+        fn id_and_size() -> Result<(), csv::Error> {
+            const DATA: &[u8] = b"# size, id, cost
+4, 10, 0.5
+2, 11, 2";
+
+            let trace = from_csv(DATA, 1, Some(0), Some(2))?;
+            let fields = trace
+                .iter()
+                .map(|i| (i.id(), i.size(), i.cost()))
+                .collect::<Vec<_>>();
+            assert_eq!(fields, vec![(10, 4, 0.5), (11, 2, 2.0)]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn missing_column() {
+            let e = from_csv(b"1,2\n3".as_slice(), 0, Some(1), None).unwrap_err();
+            assert!(e.to_string().contains("line 2: there is no column 1"));
+        }
+
+        #[test]
+        fn not_a_number() {
+            assert!(from_csv(b"abc".as_slice(), 0, None, None).is_err());
+        }
     }
 
     mod stack_distance_histograms {
@@ -419,5 +1676,65 @@ mod tests {
         entropy_test!(basic_uniform: 0,1,1,0,1,0 => 1.0);
         entropy_test!(unbalanced: 0,1,2,0,2,0,0,3 => 1.75);
         entropy_test!(precise_value: 0,1,2,0,2,0,0 => 1.37878);
+
+        #[test]
+        fn empty() {
+            assert_eq!(
+                entropy(&HashMap::<u32, u32>::new()).to_bits(),
+                0.0_f64.to_bits()
+            );
+        }
+
+        #[test]
+        fn all_zero() {
+            assert_eq!(
+                entropy(&HashMap::from([(0, 0), (1, 0)])).to_bits(),
+                0.0_f64.to_bits()
+            );
+        }
+
+        #[test]
+        fn single_symbol() {
+            // exactly positive zero, not just close to it
+            assert_eq!(
+                entropy(&HashMap::from([(0, 4)])).to_bits(),
+                0.0_f64.to_bits()
+            );
+            assert_eq!(
+                entropy(&HashMap::from([(0, 4), (1, 0)])).to_bits(),
+                0.0_f64.to_bits()
+            );
+        }
+
+        #[test]
+        fn zero_counts_are_ignored() {
+            let with_zero = HashMap::from([(0, 1), (1, 1), (2, 0)]);
+            assert!((entropy(&with_zero) - 1.0).abs() <= 1e-10);
+        }
+
+        #[test]
+        fn nats() {
+            let histogram =
+                Trace::from(vec![0, 1, 2, 0, 2, 0, 0]).frequency_histogram(&NoCondition);
+
+            assert!((entropy_base(&histogram, 2.0) - entropy(&histogram)).abs() <= 1e-10);
+            assert!(
+                (entropy_base(&histogram, std::f64::consts::E) - 1.37878 * std::f64::consts::LN_2)
+                    .abs()
+                    <= 0.0001
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "positive and not one")]
+        fn base_one() {
+            let _ = entropy_base(&HashMap::from([(0, 1), (1, 1)]), 1.0);
+        }
+
+        #[test]
+        #[should_panic(expected = "positive and not one")]
+        fn negative_base() {
+            let _ = entropy_base(&HashMap::from([(0, 1), (1, 1)]), -2.0);
+        }
     }
 }
@@ -0,0 +1,197 @@
+//! A cache whose items expire after a fixed time to live.
+
+use std::collections::HashMap;
+
+use crate::admission::{AdmissionPolicy, AdmitAll};
+use crate::cache::{AccessOutcome, Cache};
+use crate::item::Item;
+use crate::replacement_policy::ReplacementPolicy;
+use crate::stats::Stat;
+use crate::trace::Trace;
+
+/// A cache whose items expire a fixed time to live after they're inserted, regardless of the
+/// replacement policy, like a CDN or DNS cache.
+///
+/// Every access happens at a time. Before the access, any item which was inserted more than `ttl`
+/// before that time is invalidated from the cache, and then the access goes to the cache as usual.
+/// Hits don't refresh an item's insertion time, so a popular item still expires.
+///
+/// Expiring isn't an access, so the cache's statistic doesn't see it; the number of items which
+/// expired is counted separately.
+///
+/// ```
+/// use cache_sim::{Lru, TtlCache};
+/// use cache_sim::stats::HitCount;
+///
+/// let mut c = TtlCache::<Lru, HitCount>::new(2, 10);
+///
+/// c.access(0, 0);
+/// c.access(0, 5); // a hit
+/// c.access(0, 11); // 0 expired at time 10, so this is a miss
+///
+/// assert_eq!(c.cache().stat().0, 1);
+/// assert_eq!(c.expirations(), 1);
+/// ```
+pub struct TtlCache<
+    R: ReplacementPolicy<I>,
+    S: Stat<I> = (),
+    I: Item = u32,
+    A: AdmissionPolicy<I> = AdmitAll,
+> {
+    cache: Cache<R, S, I, A>,
+    ttl: u64,
+    inserted: HashMap<I, u64>,
+    expirations: u32,
+}
+
+impl<R: ReplacementPolicy<I>, S: Stat<I>, I: Item, A: AdmissionPolicy<I>> TtlCache<R, S, I, A> {
+    /// Give the items in an explicitly configured cache a time to live.
+    ///
+    /// Any items already in the cache are treated as inserted at time zero.
+    pub fn with_cache(cache: Cache<R, S, I, A>, ttl: u64) -> Self {
+        Self {
+            inserted: cache.set().iter().map(|item| (*item, 0)).collect(),
+            cache,
+            ttl,
+            expirations: 0,
+        }
+    }
+
+    /// Invalidate every item which was inserted more than the time to live before `now`.
+    fn expire(&mut self, now: u64) {
+        let ttl = self.ttl;
+        let expired = self
+            .inserted
+            .iter()
+            .filter(|(_, inserted)| now.saturating_sub(**inserted) > ttl)
+            .map(|(item, _)| *item)
+            .collect::<Vec<_>>();
+
+        for item in expired {
+            self.inserted.remove(&item);
+            self.cache.invalidate(item);
+            self.expirations += 1;
+        }
+    }
+
+    /// Update the cache after an access to item at time `now`, returning whether it hit and what
+    /// was evicted by the replacement policy.
+    ///
+    /// Times should be non-decreasing over the accesses.
+    ///
+    /// # Panics
+    ///
+    /// If the replacement policy errors, and so we end up over capacity.
+    pub fn access_outcome(&mut self, item: I, now: u64) -> AccessOutcome<I> {
+        self.expire(now);
+
+        let outcome = self.cache.access_outcome(item);
+        if let AccessOutcome::Miss { evicted } = &outcome {
+            for evicted in evicted {
+                self.inserted.remove(evicted);
+            }
+            if self.cache.contains(item) {
+                self.inserted.insert(item, now);
+            }
+        }
+
+        outcome
+    }
+
+    /// Update the cache after an access to item at time `now`.
+    ///
+    /// # Panics
+    ///
+    /// If the replacement policy errors, and so we end up over capacity.
+    pub fn access(&mut self, item: I, now: u64) {
+        self.access_outcome(item, now);
+    }
+
+    /// Update the cache after accessing all items in the trace.
+    ///
+    /// Each access happens at its timestamp, or at its position in the trace if the trace doesn't
+    /// have timestamps.
+    pub fn run_trace(&mut self, trace: &Trace<I>) {
+        match trace.timestamps() {
+            Some(timestamps) => {
+                for (item, now) in trace.iter().zip(timestamps) {
+                    self.access(*item, *now);
+                }
+            }
+            None => {
+                for (item, now) in trace.iter().zip(0..) {
+                    self.access(*item, now);
+                }
+            }
+        }
+    }
+
+    /// Get a reference to the underlying cache.
+    pub const fn cache(&self) -> &Cache<R, S, I, A> {
+        &self.cache
+    }
+
+    /// Get the time to live of each item.
+    pub const fn ttl(&self) -> u64 {
+        self.ttl
+    }
+
+    /// Get the number of items which have expired.
+    pub const fn expirations(&self) -> u32 {
+        self.expirations
+    }
+}
+
+impl<R: ReplacementPolicy<I> + Default, S: Stat<I>, I: Item, A: AdmissionPolicy<I>>
+    TtlCache<R, S, I, A>
+{
+    /// Create an empty cache whose items live for `ttl`, using the default parameters for the
+    /// replacement policy.
+    #[must_use]
+    pub fn new(capacity: u32, ttl: u64) -> Self {
+        Self::with_cache(Cache::new(capacity), ttl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::atf::parse;
+    use crate::stats::MissCount;
+    use crate::{GeneralModelItem, Lru};
+
+    #[test]
+    fn expired_reaccess_misses() -> Result<(), csv::Error> {
+        let trace = Trace::from_records(parse(
+            b"0,0,R,1,1\n1,3,R,1,1\n0,5,R,1,1\n0,20,R,1,1".as_slice(),
+        )?);
+
+        let mut ttl = TtlCache::<Lru<_>, MissCount, GeneralModelItem>::new(4, 10);
+        ttl.run_trace(&trace);
+
+        let mut no_ttl = Cache::<Lru<_>, MissCount, GeneralModelItem>::new(4);
+        no_ttl.run_trace(&trace);
+
+        // the last access to 0 is a hit without the time to live
+        assert_eq!(ttl.cache().stat().0, 3);
+        assert_eq!(no_ttl.stat().0, 2);
+        // both items expired, even though 0 hit in between
+        assert_eq!(ttl.expirations(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn evicted_items_dont_expire() {
+        let mut c = TtlCache::<Lru>::new(1, 2);
+        c.access(0, 0);
+        c.access(1, 1);
+        c.access(1, 4);
+
+        // 0 was evicted by LRU before it could expire, and 1 expired just before time 4
+        assert_eq!(c.expirations(), 1);
+        assert_eq!(c.cache().set(), &HashSet::from([1]));
+    }
+}